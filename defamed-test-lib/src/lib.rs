@@ -46,13 +46,119 @@ pub mod inner {
     }
 }
 
+/// Deeply nested module
+pub mod net {
+    pub mod http {
+        pub mod client {
+            /// Build a request line
+            #[defamed::defamed(net::http::client)]
+            pub fn request(path: &str, #[def("GET")] method: &str) -> String {
+                format!("{} {}", method, path)
+            }
+
+            /// Same path, prefixed with `crate`
+            #[defamed::defamed(crate::net::http::client, strategy = linear)]
+            pub fn port(#[def(80)] port: u16) -> u16 {
+                port
+            }
+        }
+    }
+}
+
+/// Module names containing underscores
+pub mod net_http {
+    pub mod client {
+        /// Same function name as [crate::net::http::client::request]
+        #[defamed::defamed(net_http::client)]
+        pub fn request(path: &str) -> String {
+            path.to_string()
+        }
+    }
+}
+
+/// Internal layers with restricted visibility
+pub mod layers {
+    pub mod storage {
+        /// Only visible to the parent module
+        #[defamed::defamed(layers::storage)]
+        pub(super) fn read(key: &str, #[def(0)] offset: usize) -> String {
+            format!("read {}+{}", key, offset)
+        }
+
+        /// Only visible inside `layers`
+        #[defamed::defamed(crate::layers::storage)]
+        pub(in crate::layers) fn write(key: &str, #[def] append: bool) -> String {
+            format!("write {} {}", key, append)
+        }
+    }
+
+    pub mod service {
+        /// Uses the storage layer from a sibling module
+        pub fn handle(key: &str) -> String {
+            format!(
+                "{}, {}",
+                super::storage::read!(key),
+                super::storage::write!(key, append = true)
+            )
+        }
+
+        pub mod audit {
+            /// Uses the storage layer from a module two levels below `layers`
+            pub fn log(key: &str) -> String {
+                super::super::storage::write!(key)
+            }
+        }
+    }
+
+    /// Uses the storage layer from the parent module
+    pub fn read_at(key: &str, offset: usize) -> String {
+        storage::read!(key, offset)
+    }
+}
+
+/// Module paths of annotated items are inferred.
+#[defamed::scope]
+pub mod scoped {
+    /// Scale a value
+    #[defamed::defamed]
+    pub fn scale(value: i32, #[def(2)] factor: i32) -> i32 {
+        value * factor
+    }
+
+    /// Private functions are wrapped as well
+    #[defamed::defamed]
+    fn offset(value: i32, #[def(1)] by: i32) -> i32 {
+        value + by
+    }
+
+    /// Uses the private function from a child module
+    pub fn scale_and_offset(value: i32) -> i32 {
+        nested::scaled_offset(value)
+    }
+
+    pub mod nested {
+        /// Point in a nested module
+        #[defamed::defamed(strategy = linear)]
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Point {
+            pub x: i32,
+            #[def]
+            pub y: i32,
+        }
+
+        pub(super) fn scaled_offset(value: i32) -> i32 {
+            super::offset!(super::scale!(value))
+        }
+    }
+}
+
 /// Some struct definition
 #[defamed::defamed(crate)]
 pub struct DefaultStruct<'a> {
     pub index: usize,
     #[def]
     pub offset: usize,
-    #[def((&[]))]
+    #[def(&[])]
     pub inner: &'a [u8],
 }
 
@@ -63,59 +169,470 @@ impl<'a> DefaultStruct<'a> {
     }
 }
 
+/// Struct with methods wrapped by `defamed`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counter {
+    pub count: i32,
+    pub step: i32,
+}
+
+#[defamed::defamed(crate, partial, signature)]
+impl Counter {
+    /// Create a new counter
+    pub fn new(#[def] count: i32, #[def(1)] step: i32) -> Self {
+        Self { count, step }
+    }
+
+    /// Increment the counter by `times` steps, returning the new count
+    pub fn increment(&mut self, #[def(1)] times: i32, #[def] reverse: bool) -> i32 {
+        match reverse {
+            true => self.count -= self.step * times,
+            false => self.count += self.step * times,
+        }
+
+        self.count
+    }
+
+    /// Count after `times` steps, stepping backwards if `times` is negative
+    pub fn peek(&self, times: i32, #[def(times < 0)] reverse: bool) -> i32 {
+        match reverse {
+            true => self.count - self.step * times.abs(),
+            false => self.count + self.step * times,
+        }
+    }
+
+    /// Count plus any number of extra values
+    pub fn count_with(&self, #[rest] extra: &[i32]) -> i32 {
+        self.count + extra.iter().sum::<i32>()
+    }
+
+    /// Count converted to another type
+    pub fn count_as<T: From<i32>>(&self, #[def] offset: i32) -> T {
+        T::from(self.count + offset)
+    }
+
+    /// Consume the counter, returning the count
+    pub fn into_count(self) -> i32 {
+        self.count
+    }
+
+    /// Whether the count is equal to a value
+    pub fn r#match(&self, #[def] value: i32) -> bool {
+        self.count == value
+    }
+}
+
+impl Counter {
+    /// Not wrapped: this `impl` block is not annotated
+    pub fn step(&self) -> i32 {
+        self.step
+    }
+}
+
+/// Only annotated methods are wrapped
+#[defamed::defamed(crate)]
+impl DefaultTupleStruct {
+    /// Sum of all fields and the offset
+    #[defamed::defamed(strategy = linear)]
+    pub fn with_offset(&self, #[def(1)] offset: usize) -> usize {
+        self.0 + self.1 + offset
+    }
+
+    /// Not wrapped: other methods are annotated
+    pub fn not_wrapped(&self, _unused: usize) {}
+}
+
+/// Trait with default parameters on its methods
+#[defamed::defamed(crate)]
+pub trait Greeter {
+    /// Create a greeter from a greeting
+    fn create(#[def("Hello")] greeting: &'static str) -> Self;
+
+    /// Greet someone
+    fn greet(&self, name: &str, #[def] excited: bool) -> String;
+
+    /// Greet someone multiple times
+    fn greet_many(&self, name: &str, #[def(2)] times: usize) -> String {
+        vec![self.greet(name, false); times].join(" ")
+    }
+}
+
+/// Greeter implementation
+pub struct SimpleGreeter(&'static str);
+
+impl Greeter for SimpleGreeter {
+    fn create(greeting: &'static str) -> Self {
+        Self(greeting)
+    }
+
+    fn greet(&self, name: &str, excited: bool) -> String {
+        format!("{}, {}{}", self.0, name, if excited { "!" } else { "." })
+    }
+}
+
 /// Some struct tuple definition
 #[defamed::defamed(crate)]
 #[derive(Clone, Debug, PartialEq)]
 pub struct DefaultTupleStruct(pub usize, #[def] pub usize, #[def('a')] pub char);
 
-#[defamed::defamed]
-fn complex_function(
-    lhs: i32,
-    rhs: i32,
-    // literals can be used as default values
-    #[def(true)] add: bool,
-    // if no default value is provided, the type must implement Default
-    #[def] divide_result_by: Option<i32>,
+/// Enum with default variant fields
+#[defamed::defamed(crate)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+        #[def((0.0, 0.0))]
+        center: (f64, f64),
+    },
+    Rect(f64, #[def(1.0)] f64),
+    r#Line(#[def(1.0)] f64),
+    Empty,
+}
+
+/// Define a function as written, and again as `$linear` with the linear macro generation strategy,
+/// so that both strategies are tested with the same signature.
+macro_rules! with_linear {
+    (
+        $linear:ident,
+        $(#[doc = $doc:literal])*
+        #[defamed::defamed($($opts:tt)*)]
+        $vis:vis fn $name:ident($($params:tt)*) -> $ret:ty $body:block
+    ) => {
+        $(#[doc = $doc])*
+        #[defamed::defamed($($opts)*)]
+        $vis fn $name($($params)*) -> $ret $body
+
+        #[doc = concat!("Same as [", stringify!($name), "], using the linear macro generation strategy.")]
+        #[defamed::defamed(crate, strategy = linear)]
+        pub fn $linear($($params)*) -> $ret $body
+    };
+}
+
+with_linear! {
+    linear_function,
+    #[defamed::defamed(partial, signature)]
+    fn complex_function(
+        lhs: i32,
+        rhs: i32,
+        // literals can be used as default values
+        #[def(true)] add: bool,
+        // if no default value is provided, the type must implement Default
+        #[def] divide_result_by: Option<i32>,
+    ) -> i32 {
+        let intermediate = if add { lhs + rhs } else { lhs - rhs };
+
+        match divide_result_by {
+            Some(div) => intermediate / div,
+            None => intermediate,
+        }
+    }
+}
+
+/// Any expression can be used as a default value.
+#[defamed::defamed(crate)]
+pub fn expression_defaults(
+    #[def(Vec::new())] items: Vec<u8>,
+    #[def(1 + 2)] sum: i32,
+    #[def(Counter { count: 4, step: 2 })] counter: Counter,
+    #[def({
+        let base = 10;
+        base * base
+    })]
+    block: i32,
+) -> (usize, i32, i32, i32) {
+    (items.len(), sum, counter.count, block)
+}
+
+/// Greeting taking ownership of a value that is not `Copy`.
+#[defamed::defamed(crate, partial)]
+pub fn greet(greeting: String, name: &str) -> String {
+    format!("{}, {}", greeting, name)
+}
+
+with_linear! {
+    linear_sub_slice,
+    /// Default values can refer to preceding parameters.
+    #[defamed::defamed(crate, partial)]
+    pub fn sub_slice(buf: &[u8], #[def(0)] start: usize, #[def(buf.len())] end: usize) -> &[u8] {
+        &buf[start..end]
+    }
+}
+
+with_linear! {
+    linear_parameter_patterns,
+    /// Parameters can be declared with any pattern accepted by functions.
+    #[defamed::defamed(crate, partial)]
+    pub fn parameter_patterns(
+        mut count: i32,
+        #[name(point)] (x, y): (i32, i32),
+        #[name(counter)] Counter { step, .. }: Counter,
+        #[name(r#in)] (lo, hi): (i32, i32),
+        #[def(count * point.0)] r#type: i32,
+    ) -> (i32, i32, i32, i32) {
+        count += step;
+        (count, x * y, hi - lo, r#type)
+    }
+}
+
+/// Struct fields with dependent default values
+#[defamed::defamed(crate)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rectangle {
+    pub width: u32,
+    #[def(width)]
+    pub height: u32,
+    #[def(width * height)]
+    pub area: u32,
+}
+
+/// Optional parameters default to `None`, supplied values are wrapped in `Some`.
+#[defamed::defamed(crate)]
+pub fn connect(host: &str, #[opt] port: Option<u16>, #[opt] timeout: Option<u64>) -> String {
+    format!("{}:{:?}:{:?}", host, port, timeout)
+}
+
+/// All trailing `Option<T>` parameters are optional.
+#[defamed::defamed(crate, option)]
+pub fn connect_via(
+    // not trailing, so this parameter is required and not wrapped
+    proxy: Option<&str>,
+    host: &str,
+    port: Option<u16>,
+    // explicit default values are not wrapped
+    #[def(Some(3))] retries: Option<u8>,
+    timeout: Option<u64>,
+) -> String {
+    format!(
+        "{:?}->{}:{:?}:{:?}:{:?}",
+        proxy, host, port, retries, timeout
+    )
+}
+
+/// Struct with optional fields
+#[defamed::defamed(crate, option, strategy = linear)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Endpoint {
+    pub host: &'static str,
+    pub port: Option<u16>,
+    #[def(Some(30))]
+    pub timeout: Option<u64>,
+}
+
+/// Remaining positional arguments are collected into a slice.
+#[defamed::defamed(crate, partial)]
+pub fn log(level: u8, #[def(">")] prefix: &str, #[rest] messages: &[&str]) -> String {
+    format!("{}{} {}", prefix, level, messages.join(" "))
+}
+
+/// Remaining positional arguments are collected into a `Vec`.
+#[defamed::defamed(crate, strategy = linear)]
+pub fn sum_all(#[def(0)] init: i32, #[rest] values: Vec<i32>) -> i32 {
+    values.into_iter().fold(init, |acc, v| acc + v)
+}
+
+/// Remaining positional arguments are collected into an array.
+#[defamed::defamed(crate)]
+pub fn rgb(#[rest] channels: [u8; 3]) -> u32 {
+    channels.into_iter().fold(0, |acc, c| (acc << 8) | c as u32)
+}
+
+/// Flags can only be passed by name.
+#[defamed::defamed(crate)]
+pub fn copy(
+    src: &str,
+    dst: &str,
+    #[kw] dry_run: bool,
+    #[kw]
+    #[def]
+    overwrite: bool,
+    #[kw]
+    #[def(true)]
+    follow_links: bool,
+) -> String {
+    format!(
+        "{}->{}:{}:{}:{}",
+        src, dst, dry_run, overwrite, follow_links
+    )
+}
+
+/// Keyword-only parameters with the linear strategy.
+#[defamed::defamed(crate, strategy = linear)]
+pub fn linear_copy(
+    src: &str,
+    #[def("out")] dst: &str,
+    #[kw]
+    #[def]
+    overwrite: bool,
+) -> String {
+    format!("{}->{}:{}", src, dst, overwrite)
+}
+
+/// Struct with keyword-only fields
+#[defamed::defamed(crate)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Retry {
+    pub attempts: u8,
+    #[kw]
+    #[def]
+    pub backoff: bool,
+    #[kw]
+    #[def(true)]
+    pub jitter: bool,
+}
+
+/// Positional-only parameters can be renamed without breaking callers.
+#[defamed::defamed(crate)]
+pub fn clamp(
+    #[pos] value: i32,
+    #[pos]
+    #[def(0)]
+    lower: i32,
+    #[def(100)] upper: i32,
+) -> i32 {
+    value.max(lower).min(upper)
+}
+
+/// Positional-only parameters with the linear strategy.
+#[defamed::defamed(crate, strategy = linear)]
+pub fn linear_clamp(#[pos] value: i32, #[def(0)] lower: i32, #[def(100)] upper: i32) -> i32 {
+    value.max(lower).min(upper)
+}
+
+/// Struct with a positional-only field
+#[defamed::defamed(crate)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interval {
+    #[pos]
+    pub start: u32,
+    #[def(u32::MAX)]
+    pub end: u32,
+}
+
+/// Renamed parameters are still accepted by their old names.
+#[defamed::defamed(crate)]
+pub fn resize(
+    #[alias(w)] width: u32,
+    #[alias(h)] height: u32,
+    #[deprecated_name(keep_ratio)]
+    #[def(true)]
+    preserve_aspect: bool,
+) -> String {
+    format!("{}x{}:{}", width, height, preserve_aspect)
+}
+
+/// Aliases with the linear strategy.
+#[defamed::defamed(crate, strategy = linear)]
+pub fn linear_resize(
+    #[alias(w, cols)] width: u32,
+    #[deprecated_name(h)]
+    #[def(1)]
+    height: u32,
+) -> String {
+    format!("{}x{}", width, height)
+}
+
+/// Struct with renamed fields
+#[defamed::defamed(crate)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Size {
+    #[alias(w)]
+    pub width: u32,
+    #[deprecated_name(h)]
+    #[def(1)]
+    pub height: u32,
+}
+
+/// Struct using the linear macro generation strategy.
+#[defamed::defamed(crate, strategy = linear)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearStruct {
+    pub index: usize,
+    #[def]
+    pub offset: usize,
+    #[def('a')]
+    pub tag: char,
+}
+
+/// Too many parameters to permute, so the linear strategy is selected automatically.
+#[defamed::defamed(crate)]
+#[allow(clippy::too_many_arguments)]
+pub fn many_args(
+    a: i32,
+    b: i32,
+    c: i32,
+    d: i32,
+    e: i32,
+    f: i32,
+    #[def(7)] g: i32,
+    #[def(8)] h: i32,
+    #[def(9)] i: i32,
+    #[def(10)] j: i32,
+    #[def(11)] k: i32,
+    #[def(12)] l: i32,
+    #[def(13)] m: i32,
+    #[def(14)] n: i32,
+    #[def(15)] o: i32,
 ) -> i32 {
-    let intermediate = if add { lhs + rhs } else { lhs - rhs };
-
-    match divide_result_by {
-        Some(div) => intermediate / div,
-        None => intermediate,
-    }
-}
-
-// #[defamed::defamed]
-// fn all_default(
-//     #[def(1)] a: i32,
-//     #[def(2)] b: i32,
-//     #[def(3)] c: i32,
-//     // #[def(4)] d: i32,
-//     // #[def(5)] e: i32,
-// ) -> i32 {
-//     a + b + c
-// }
-
-// generate a function with 10 positional arguments and 5 default arguments
-// defamed::defamed! {
-// #[defamed::defamed(crate)]
-// pub fn many_args(
-//     #[def(1)] a: i32,
-//     #[def(2)] b: i32,
-//     #[def(3)] c: i32,
-//     #[def(4)] d: i32,
-//     #[def(5)] e: i32,
-//     #[def(6)] f: i32,
-//     #[def(7)] g: i32,
-//     // #[def(8)] h: i32,
-//     // #[def(9)] i: i32,
-//     // #[def(10)] j: i32,
-//     // #[def(11)] k: i32,
-//     // #[def(12)] l: i32,
-//     // #[def(13)] m: i32,
-//     // #[def(14)] n: i32,
-//     // #[def(15)] o: i32,
-// ) -> i32 {
-//     a + b + c + d + e + f + g
-// }
-// // }
+    [a, b, c, d, e, f, g, h, i, j, k, l, m, n, o]
+        .iter()
+        .enumerate()
+        .map(|(idx, val)| (idx as i32 + 1) * val)
+        .sum()
+}
+
+/// Generic function whose return type cannot be inferred from its arguments.
+#[defamed::defamed(crate)]
+pub fn parse_or<T: std::str::FromStr>(s: &str, fallback: T, #[def(10)] radix: u32) -> T {
+    match radix {
+        10 => s.parse().unwrap_or(fallback),
+        _ => fallback,
+    }
+}
+
+/// Generic function with a const parameter, using the linear macro generation strategy.
+#[defamed::defamed(crate, strategy = linear)]
+pub fn repeat<const N: usize, T: Copy + Default>(#[def] value: T) -> [T; N] {
+    [value; N]
+}
+
+/// Generic struct with a const parameter
+#[defamed::defamed(crate, signature)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ring<T: Default, const N: usize> {
+    #[def]
+    pub items: [T; N],
+    #[def]
+    pub head: usize,
+}
+
+// Foreign functions of the C standard library, called inside an `unsafe` block unless declared `safe`.
+#[defamed::defamed(crate, unsafe)]
+unsafe extern "C" {
+    /// Absolute value of an integer
+    pub fn abs(#[def(-1)] x: core::ffi::c_int) -> core::ffi::c_int;
+    /// Whether a character is a decimal digit
+    pub safe fn isdigit(#[def(b'7' as core::ffi::c_int)] c: core::ffi::c_int) -> core::ffi::c_int;
+}
+
+/// Foreign functions of the C standard library, with only the annotated functions selected.
+pub mod libc_fns {
+    #[defamed::defamed(crate::libc_fns)]
+    unsafe extern "C" {
+        #[defamed::defamed]
+        pub fn labs(#[def(-1)] x: core::ffi::c_long) -> core::ffi::c_long;
+        #[defamed::defamed]
+        pub safe fn tolower(
+            #[def(b'A' as core::ffi::c_int)] c: core::ffi::c_int,
+        ) -> core::ffi::c_int;
+        pub fn strlen(s: *const core::ffi::c_char) -> usize;
+    }
+}
+
+/// Unsafe function with a default value referring to a preceding parameter.
+///
+/// # Safety
+/// `ptr` must be valid for reads at `ptr + offset` and `ptr + 1`.
+#[defamed::defamed(crate, unsafe)]
+pub unsafe fn read_pair(ptr: *const u8, #[def(1)] offset: usize) -> (u8, u8) {
+    (*ptr, *ptr.add(offset))
+}
@@ -57,6 +57,513 @@ fn test_default_tuple_struct() {
     assert_eq!(c, DefaultTupleStruct(2, 4, 'f'));
 }
 
+#[test]
+fn test_linear_function() {
+    assert_eq!(linear_function!(10, 5), 15);
+    assert_eq!(linear_function!(10, 5, add = false), 5);
+    assert_eq!(linear_function!(10, 20, divide_result_by = Some(2)), 15);
+    assert_eq!(linear_function!(20, 10, false, Some(2)), 5);
+    assert_eq!(linear_function!(20, rhs = 10, add = false,), 10);
+
+    // named parameters in any order, including defaults before required parameters
+    assert_eq!(
+        linear_function!(divide_result_by = Some(2), rhs = 10, add = false, lhs = 20),
+        5
+    );
+}
+
+#[test]
+fn test_linear_struct() {
+    let reference = LinearStruct {
+        index: 1,
+        offset: 0,
+        tag: 'a',
+    };
+
+    assert_eq!(LinearStruct! {index: 1, ..}, reference);
+    assert_eq!(LinearStruct! {1, ..}, reference);
+    assert_eq!(LinearStruct! {tag: 'a', index: 1, offset: 0}, reference);
+    assert_eq!(LinearStruct! {1, 0, 'a'}, reference);
+}
+
+#[test]
+fn test_many_args() {
+    let weighted_sum = |values: [i32; 15]| -> i32 {
+        values
+            .iter()
+            .enumerate()
+            .map(|(idx, val)| (idx as i32 + 1) * val)
+            .sum()
+    };
+
+    let reference = weighted_sum([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    assert_eq!(many_args!(1, 2, 3, 4, 5, 6), reference);
+    assert_eq!(
+        many_args!(f = 6, e = 5, d = 4, c = 3, b = 2, a = 1),
+        reference
+    );
+    assert_eq!(
+        many_args!(1, 2, 3, 4, 5, 6, o = 0, g = 0),
+        weighted_sum([1, 2, 3, 4, 5, 6, 0, 8, 9, 10, 11, 12, 13, 14, 0])
+    );
+    assert_eq!(many_args!(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1), 15);
+}
+
+#[test]
+fn test_impl_methods() {
+    assert_eq!(Counter_new!(), Counter { count: 0, step: 1 });
+    assert_eq!(Counter_new!(step = 2), Counter { count: 0, step: 2 });
+
+    let mut counter = Counter_new!(10, 5);
+    assert_eq!(Counter_increment!(&mut counter), 15);
+    assert_eq!(Counter_increment!(&mut counter, 2), 25);
+    assert_eq!(Counter_increment!(&mut counter, reverse = true), 20);
+    assert_eq!(
+        Counter_increment!(&mut counter, reverse = true, times = 4),
+        0
+    );
+    assert_eq!(counter.step(), 5);
+
+    assert_eq!(Counter_peek!(&counter, 2), 10);
+    assert_eq!(Counter_peek!(&counter, -2), -10);
+    assert_eq!(Counter_peek!(&counter, -2, reverse = false), -10);
+    assert_eq!(Counter_count_with!(&counter), 0);
+    assert_eq!(Counter_count_with!(&counter, 1, 2), 3);
+
+    assert!(Counter_match!(&counter));
+    assert!(!Counter_match!(&counter, value = 1));
+    assert_eq!(Counter_into_count!(counter), 0);
+}
+
+#[test]
+fn test_impl_marked_methods() {
+    let tuple = DefaultTupleStruct!(1, 2);
+
+    assert_eq!(DefaultTupleStruct_with_offset!(&tuple), 4);
+    assert_eq!(DefaultTupleStruct_with_offset!(&tuple, offset = 0), 3);
+    tuple.not_wrapped(0);
+}
+
+#[test]
+fn test_trait_methods() {
+    // the implementation is inferred from the return type
+    let greeter: SimpleGreeter = Greeter_create!();
+    let hi: SimpleGreeter = Greeter_create!(greeting = "Hi");
+
+    assert_eq!(Greeter_greet!(&greeter, "world"), "Hello, world.");
+    assert_eq!(
+        Greeter_greet!(&hi, name = "you", excited = true),
+        "Hi, you!"
+    );
+    assert_eq!(Greeter_greet_many!(&hi, "a"), "Hi, a. Hi, a.");
+    assert_eq!(Greeter_greet_many!(&hi, "a", times = 1), "Hi, a.");
+}
+
+#[test]
+fn test_enum_variants() {
+    assert_eq!(
+        Shape_Circle! {radius: 2.0, ..},
+        Shape::Circle {
+            radius: 2.0,
+            center: (0.0, 0.0)
+        }
+    );
+    assert_eq!(
+        Shape_Circle! {radius: 2.0, center: (1.0, 1.0)},
+        Shape::Circle {
+            radius: 2.0,
+            center: (1.0, 1.0)
+        }
+    );
+
+    assert_eq!(Shape_Rect!(2.0), Shape::Rect(2.0, 1.0));
+    assert_eq!(Shape_Rect!(2.0, 3.0), Shape::Rect(2.0, 3.0));
+    assert_eq!(Shape_Line!(), Shape::Line(1.0));
+    assert_eq!(Shape::Empty, Shape::Empty);
+}
+
+#[test]
+fn test_expression_defaults() {
+    assert_eq!(expression_defaults!(), (0, 3, 4, 100));
+    assert_eq!(
+        expression_defaults!(vec![1, 2], block = -1, sum = 0),
+        (2, 0, 4, -1)
+    );
+}
+
+#[test]
+fn test_dependent_defaults() {
+    let buf = [1, 2, 3, 4];
+
+    assert_eq!(sub_slice!(&buf), &[1, 2, 3, 4]);
+    assert_eq!(sub_slice!(&buf, 1), &[2, 3, 4]);
+    assert_eq!(sub_slice!(&buf, end = 2), &[1, 2]);
+    assert_eq!(linear_sub_slice!(&buf, end = 3, start = 1), &[2, 3]);
+    assert_eq!(linear_sub_slice!(buf = &buf[1..]), &[2, 3, 4]);
+
+    // temporaries live until the call returns
+    assert_eq!(sub_slice!(String::from("abc").as_bytes(), 1), b"bc");
+
+    // call site variables are not shadowed by the bindings
+    let end = 1;
+    assert_eq!(sub_slice!(&buf, end = end), &[1]);
+
+    assert_eq!(
+        Rectangle! {width: 2, ..},
+        Rectangle {
+            width: 2,
+            height: 2,
+            area: 4
+        }
+    );
+    assert_eq!(
+        Rectangle! {width: 2, height: 3, ..},
+        Rectangle {
+            width: 2,
+            height: 3,
+            area: 6
+        }
+    );
+}
+
+#[test]
+fn test_nested_modules() {
+    assert_eq!(net::http::client::request!("/"), "GET /");
+    assert_eq!(net::http::client::request!("/", method = "POST"), "POST /");
+    assert_eq!(net::http::client::port!(), 80);
+    assert_eq!(net::http::client::port!(port = 8080), 8080);
+
+    assert_eq!(net_http::client::request!("/"), "/");
+}
+
+#[test]
+fn test_restricted_visibility() {
+    assert_eq!(layers::service::handle("a"), "read a+0, write a true");
+    assert_eq!(layers::read_at("b", 2), "read b+2");
+    assert_eq!(layers::service::audit::log("c"), "write c false");
+}
+
+#[test]
+fn test_scoped_module() {
+    assert_eq!(scoped::scale!(3), 6);
+    assert_eq!(scoped::scale!(3, factor = 3), 9);
+    assert_eq!(scoped::scale_and_offset(3), 7);
+
+    assert_eq!(
+        scoped::nested::Point! {x: 1, ..},
+        scoped::nested::Point { x: 1, y: 0 }
+    );
+}
+
+#[test]
+fn test_optional_params() {
+    assert_eq!(connect!("localhost"), "localhost:None:None");
+    assert_eq!(connect!("localhost", 80), "localhost:Some(80):None");
+    assert_eq!(connect!("localhost", timeout = 5), "localhost:None:Some(5)");
+
+    assert_eq!(
+        connect_via!(None, "localhost"),
+        "None->localhost:None:Some(3):None"
+    );
+    assert_eq!(
+        connect_via!(Some("proxy"), "localhost", 80, Some(1), timeout = 5),
+        "Some(\"proxy\")->localhost:Some(80):Some(1):Some(5)"
+    );
+
+    assert_eq!(
+        Endpoint! {host: "localhost", ..},
+        Endpoint {
+            host: "localhost",
+            port: None,
+            timeout: Some(30)
+        }
+    );
+    assert_eq!(
+        Endpoint! {port: 80, host: "localhost", ..},
+        Endpoint {
+            host: "localhost",
+            port: Some(80),
+            timeout: Some(30)
+        }
+    );
+}
+
+#[test]
+fn test_rest_params() {
+    assert_eq!(log!(1), ">1 ");
+    assert_eq!(log!(1, prefix = "#"), "#1 ");
+    assert_eq!(log!(1, "#", "a"), "#1 a");
+    assert_eq!(log!(2, "#", "a", "b", "c"), "#2 a b c");
+
+    assert_eq!(sum_all!(), 0);
+    assert_eq!(sum_all!(init = 5), 5);
+    assert_eq!(sum_all!(1, 2, 3, 4), 10);
+    assert_eq!(sum_all!(1, 2, 3, 4,), 10);
+
+    assert_eq!(rgb!(0xff, 0x80, 0x00), 0xff8000);
+}
+
+#[test]
+fn test_kw_only_params() {
+    assert_eq!(copy!("a", "b", dry_run = true), "a->b:true:false:true");
+    assert_eq!(
+        copy!("a", dst = "b", dry_run = false, overwrite = true),
+        "a->b:false:true:true"
+    );
+    assert_eq!(
+        copy!(dry_run = false, dst = "b", src = "a", follow_links = false),
+        "a->b:false:false:false"
+    );
+
+    assert_eq!(linear_copy!("a"), "a->out:false");
+    assert_eq!(linear_copy!("a", "b", overwrite = true), "a->b:true");
+    assert_eq!(linear_copy!(overwrite = true, src = "a"), "a->out:true");
+
+    assert_eq!(
+        Retry! {3, ..},
+        Retry {
+            attempts: 3,
+            backoff: false,
+            jitter: true
+        }
+    );
+    assert_eq!(
+        Retry! {attempts: 3, jitter: false, ..},
+        Retry {
+            attempts: 3,
+            backoff: false,
+            jitter: false
+        }
+    );
+}
+
+#[test]
+fn test_pos_only_params() {
+    assert_eq!(clamp!(150), 100);
+    assert_eq!(clamp!(-5, 1), 1);
+    assert_eq!(clamp!(50, upper = 10), 10);
+    assert_eq!(clamp!(-5, upper = 10), 0);
+    assert_eq!(clamp!(5, 1, 3), 3);
+
+    assert_eq!(linear_clamp!(150), 100);
+    assert_eq!(linear_clamp!(-5, upper = 10, lower = -1), -1);
+    assert_eq!(linear_clamp!(5, 1, 3), 3);
+
+    assert_eq!(
+        Interval! {1, ..},
+        Interval {
+            start: 1,
+            end: u32::MAX
+        }
+    );
+    assert_eq!(Interval! {1, end: 5}, Interval { start: 1, end: 5 });
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_aliases() {
+    assert_eq!(resize!(w = 1, h = 2), "1x2:true");
+    assert_eq!(resize!(height = 2, w = 1), "1x2:true");
+    assert_eq!(resize!(1, h = 2, preserve_aspect = false), "1x2:false");
+    assert_eq!(resize!(1, 2, keep_ratio = false), "1x2:false");
+
+    assert_eq!(linear_resize!(w = 3), "3x1");
+    assert_eq!(linear_resize!(h = 2, cols = 3), "3x2");
+    assert_eq!(linear_resize!(3, height = 2), "3x2");
+
+    assert_eq!(
+        Size! {w: 3, ..},
+        Size {
+            width: 3,
+            height: 1
+        }
+    );
+    assert_eq!(
+        Size! {w: 3, h: 2},
+        Size {
+            width: 3,
+            height: 2
+        }
+    );
+}
+
+#[test]
+fn test_generic_arguments() {
+    assert_eq!(parse_or!(::<u64>; "42", fallback = 0), 42);
+    assert_eq!(parse_or!(::<u8>; "7", 1, radix = 16), 1);
+
+    assert_eq!(repeat!(::<3, u8>;), [0, 0, 0]);
+    assert_eq!(repeat!(::<1, Option<u8>>; value = Some(1)), [Some(1)]);
+    assert_eq!(repeat!(::<{ 1 + 1 }, char>; 'a'), ['a', 'a']);
+
+    let counter = Counter_new!(count = 2);
+    assert_eq!(Counter_count_as!(::<i64>; &counter), 2i64);
+    assert_eq!(Counter_count_as!(::<f64>; &counter, offset = 1), 3.0);
+
+    assert_eq!(
+        Ring!(::<u8, 2>; head: 1, ..),
+        Ring {
+            items: [0, 0],
+            head: 1
+        }
+    );
+}
+
+#[test]
+fn test_parameter_patterns() {
+    let counter = || Counter { count: 0, step: 2 };
+
+    assert_eq!(
+        parameter_patterns!(1, (2, 3), counter(), (0, 4)),
+        (3, 6, 4, 2)
+    );
+    assert_eq!(
+        parameter_patterns!(1, point = (2, 3), counter = counter(), in = (1, 2), type = 0),
+        (3, 6, 1, 0)
+    );
+    assert_eq!(
+        parameter_patterns!(in = (0, 1), counter = counter(), count = 1, point = (2, 3), type = 4),
+        (3, 6, 1, 4)
+    );
+
+    // destructured parameters are closure parameters bound to their name
+    let patterns = parameter_patterns_partial!(1, counter = counter());
+    assert_eq!(patterns((2, 3), (0, 4)), (3, 6, 4, 2));
+
+    assert_eq!(
+        linear_parameter_patterns!(1, (2, 3), counter(), (0, 4)),
+        (3, 6, 4, 2)
+    );
+    assert_eq!(
+        linear_parameter_patterns!(count = 1, in = (1, 2), point = (2, 3), counter = counter(), type = 0),
+        (3, 6, 1, 0)
+    );
+}
+
+#[test]
+fn test_partial() {
+    let sub = complex_function_partial!(add = false);
+    assert_eq!(sub(10, 3), 7);
+
+    let halved = (1..4)
+        .map(complex_function_partial!(10, divide_result_by = Some(2)))
+        .collect::<Vec<_>>();
+    assert_eq!(halved, [5, 6, 6]);
+
+    let bound = complex_function_partial!(1, 2);
+    assert_eq!(bound(), 3);
+
+    // arguments are evaluated once, and a clone is passed on every call
+    let mut evaluated = 0;
+    let hi = greet_partial!({
+        evaluated += 1;
+        String::from("Hi")
+    });
+    let greetings = ["Ann", "Bo"].into_iter().map(hi).collect::<Vec<_>>();
+    assert_eq!(greetings, ["Hi, Ann", "Hi, Bo"]);
+    assert_eq!(evaluated, 1);
+
+    // default values are evaluated on every call, and can refer to parameters of the closure
+    let tail = sub_slice_partial!(start = 1);
+    assert_eq!(tail(&[1, 2, 3]), &[2, 3]);
+    assert_eq!(tail(&[1]), &[] as &[u8]);
+
+    // remaining arguments are not collected
+    let info = log_partial!(prefix = "#");
+    assert_eq!(info(1), "#1 ");
+
+    // the receiver is the first parameter of the closure
+    fn twice(counter: &mut Counter, mut f: impl FnMut(&mut Counter) -> i32) -> [i32; 2] {
+        [f(counter), f(counter)]
+    }
+    let mut counter = Counter_new!(step = 2);
+    let back = twice(
+        &mut counter,
+        Counter_increment_partial!(times = 2, reverse = true),
+    );
+    assert_eq!(back, [-4, -8]);
+    let peek = Counter_peek_partial!();
+    assert_eq!(peek(&counter, 1), -6);
+
+    let count_as = Counter_count_as_partial!(::<i64>;);
+    assert_eq!(count_as(&counter), -8i64);
+}
+
+#[test]
+fn test_foreign_functions() {
+    // calls are wrapped in an `unsafe` block
+    assert_eq!(abs!(), 1);
+    assert_eq!(abs!(x = -5), 5);
+
+    // without the option, the caller provides the `unsafe` block
+    assert_eq!(unsafe { libc_fns::labs!() }, 1);
+    assert_eq!(unsafe { libc_fns::labs!(x = -7) }, 7);
+    assert_eq!(unsafe { libc_fns::strlen(c"four".as_ptr()) }, 4);
+
+    // `safe` functions are called without an `unsafe` block
+    assert_ne!(isdigit!(), 0);
+    assert_eq!(isdigit!(c = b'x' as core::ffi::c_int), 0);
+    assert_eq!(libc_fns::tolower!(), b'a' as core::ffi::c_int);
+    assert_eq!(
+        libc_fns::tolower!(b'Q' as core::ffi::c_int),
+        b'q' as core::ffi::c_int
+    );
+
+    let bytes = [1u8, 2, 3];
+    let ptr = bytes.as_ptr();
+    assert_eq!(read_pair!(ptr), (1, 2));
+    assert_eq!(read_pair!(ptr = ptr, offset = 2), (1, 3));
+}
+
+#[test]
+fn test_signature() {
+    assert_eq!(
+        __DEFAMED_SIG_complex_function,
+        [
+            ("lhs", "i32", 0, None),
+            ("rhs", "i32", 1, None),
+            ("add", "bool", 2, Some("true")),
+            (
+                "divide_result_by",
+                "Option<i32>",
+                3,
+                Some("Default::default()")
+            ),
+        ]
+    );
+
+    // optional parameters can be enumerated
+    let optional = __DEFAMED_SIG_complex_function
+        .iter()
+        .filter(|(_, _, _, default)| default.is_some())
+        .map(|(name, ..)| *name)
+        .collect::<Vec<_>>();
+    assert_eq!(optional, ["add", "divide_result_by"]);
+
+    // the receiver is the first parameter
+    assert_eq!(
+        __DEFAMED_SIG_Counter_peek,
+        [
+            ("self", "&Self", 0, None),
+            ("times", "i32", 1, None),
+            ("reverse", "bool", 2, Some("times < 0")),
+        ]
+    );
+    assert_eq!(
+        __DEFAMED_SIG_Counter_count_with,
+        [("self", "&Self", 0, None), ("extra", "&[i32]", 1, None)]
+    );
+
+    assert_eq!(
+        __DEFAMED_SIG_Ring,
+        [
+            ("items", "[T; N]", 0, Some("Default::default()")),
+            ("head", "usize", 1, Some("Default::default()")),
+        ]
+    );
+}
+
 #[test]
 fn test_all_default() {
     // let a = all_default!();
@@ -0,0 +1,16 @@
+#![deny(deprecated)]
+
+#[defamed::defamed]
+fn area(width: u32, #[deprecated_name(h)] height: u32) -> u32 {
+    width * height
+}
+
+#[defamed::defamed(strategy = linear)]
+fn linear_area(width: u32, #[deprecated_name(h)] height: u32) -> u32 {
+    width * height
+}
+
+fn main() {
+    let _: u32 = area!(1, h = 2);
+    let _: u32 = linear_area!(1, h = 2);
+}
@@ -0,0 +1,8 @@
+#[defamed::defamed]
+fn divide(lhs: i32, rhs: i32) -> i32 {
+    lhs / rhs
+}
+
+fn main() {
+    let _: i32 = divide!(lhs = 1, lhs = 2, rhs = 3);
+}
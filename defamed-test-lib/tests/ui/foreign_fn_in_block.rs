@@ -0,0 +1,6 @@
+extern "C" {
+    #[defamed::defamed]
+    fn abs(#[def(-1)] x: core::ffi::c_int) -> core::ffi::c_int;
+}
+
+fn main() {}
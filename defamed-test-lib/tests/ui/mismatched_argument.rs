@@ -0,0 +1,8 @@
+#[defamed::defamed]
+fn request(path: &str, #[def("GET")] method: &str) -> String {
+    format!("{} {}", method, path)
+}
+
+fn main() {
+    let _: String = request!("/", method = 1);
+}
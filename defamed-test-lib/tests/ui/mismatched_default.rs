@@ -0,0 +1,6 @@
+#[defamed::defamed]
+fn request(path: &str, #[def(80)] method: &str) -> String {
+    format!("{} {}", method, path)
+}
+
+fn main() {}
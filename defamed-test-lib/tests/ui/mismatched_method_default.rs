@@ -0,0 +1,26 @@
+struct Counter(i32);
+
+#[defamed::defamed]
+impl Counter {
+    fn add(&self, #[def("one")] step: i32) -> i32 {
+        self.0 + step
+    }
+
+    fn scale(&self, #[def(Self::FACTOR)] factor: i32) -> i32 {
+        self.0 * factor
+    }
+
+    const FACTOR: i32 = 2;
+}
+
+#[defamed::defamed]
+trait Step<T> {
+    fn step(&self, #[def(1.5)] by: i32) -> T;
+}
+
+#[defamed::defamed]
+extern "C" {
+    fn abs(#[def(-1.0)] x: i32) -> i32;
+}
+
+fn main() {}
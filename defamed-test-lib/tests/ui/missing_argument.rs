@@ -0,0 +1,8 @@
+#[defamed::defamed]
+fn divide(lhs: i32, rhs: i32, #[def(false)] round_up: bool) -> i32 {
+    if round_up { (lhs + rhs - 1) / rhs } else { lhs / rhs }
+}
+
+fn main() {
+    let _: i32 = divide!(1, round_up = true);
+}
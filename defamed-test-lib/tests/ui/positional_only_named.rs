@@ -0,0 +1,8 @@
+#[defamed::defamed]
+fn divide(#[pos] lhs: i32, rhs: i32) -> i32 {
+    lhs / rhs
+}
+
+fn main() {
+    let _: i32 = divide!(lhs = 1, rhs = 2);
+}
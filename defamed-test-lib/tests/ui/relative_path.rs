@@ -0,0 +1,10 @@
+mod storage {
+    #[defamed::defamed(super::storage)]
+    pub(crate) fn read(key: &str, #[def(0)] offset: usize) -> String {
+        format!("read {}+{}", key, offset)
+    }
+}
+
+fn main() {
+    let _ = storage::read("key", 0);
+}
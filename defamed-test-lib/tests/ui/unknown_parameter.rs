@@ -0,0 +1,17 @@
+#[defamed::defamed]
+fn divide(lhs: i32, rhs: i32, #[def(false)] round_up: bool) -> i32 {
+    if round_up { (lhs + rhs - 1) / rhs } else { lhs / rhs }
+}
+
+#[defamed::defamed]
+struct Point {
+    x: i32,
+    #[def]
+    y: i32,
+}
+
+fn main() {
+    let _: i32 = divide!(1, 2, round = true);
+    let _: i32 = divide!(1, 2, nearest = true);
+    let _: Point = Point! { x: 1, z: 2 };
+}
@@ -3,14 +3,17 @@
 use proc_macro as pm;
 use proc_macro2 as pm2;
 use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
 
 use crate::{
-    macro_gen::{self, MacroType},
+    macro_gen::{self, MacroItem, MacroType},
+    options::{MacroOptions, Strategy},
     permute::{
+        combine_errors,
         fields::{StructField, StructFields},
         params, ParamAttr, PermutedItem,
     },
-    traits::StripAttributes,
+    traits::{StripAttributes, ToDocInfo},
 };
 
 /// Output of a processing function
@@ -40,65 +43,363 @@ impl From<pm2::TokenStream> for ProcOutput {
 }
 
 impl From<ProcOutput> for pm::TokenStream {
+    fn from(value: ProcOutput) -> Self {
+        pm2::TokenStream::from(value).into()
+    }
+}
+
+impl From<ProcOutput> for pm2::TokenStream {
     fn from(value: ProcOutput) -> Self {
         let mut modified = value.modified;
         modified.extend(value.generated);
 
-        modified.into()
+        modified
     }
 }
 
 /// Process a standalone function.
 /// The crate path of the funciton is passed as an optional parameter.
-pub fn item_fn(input: syn::ItemFn, fn_path: Option<syn::Path>) -> ProcOutput {
-    let syn::ItemFn {
-        attrs,
-        vis,
-        sig,
-        block,
-    } = input;
+pub fn item_fn(input: syn::ItemFn, opts: MacroOptions) -> ProcOutput {
+    let item = MacroItem {
+        unsafe_call: opts.unsafe_call,
+        ..MacroItem::new(
+            input.vis.clone(),
+            opts.path.clone(),
+            input.sig.ident.clone(),
+            MacroType::Function,
+        )
+    };
+
+    let (new_sig, generated) = match check_unsafe(&input.sig, &opts)
+        .and_then(|_| fn_signature(&input.sig, &Default::default(), &opts, item))
+    {
+        Ok(res) => res,
+        Err(e) => return recover(syn::Item::Fn(input), e),
+    };
+
+    ProcOutput {
+        modified: syn::ItemFn {
+            sig: new_sig,
+            ..input
+        }
+        .to_token_stream(),
+        generated,
+    }
+}
+
+/// Process all functions inside an `extern` block.
+///
+/// Foreign functions are unsafe to call unless declared `safe`,
+/// so the call is only wrapped in an `unsafe` block with the `unsafe` option,
+/// which does not apply to `safe` functions.
+/// Annotated functions are selected in the same way as [item_impl].
+pub fn item_foreign_mod(input: syn::ItemForeignMod, opts: MacroOptions) -> ProcOutput {
+    let original = input.clone();
+    let mut items = input.items;
+    // `safe` functions are only parsed from verbatim tokens
+    let mut safe_fns = items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| match item {
+            syn::ForeignItem::Verbatim(tokens) => syn::parse2::<ForeignFn>(tokens.clone())
+                .ok()
+                .filter(|f| f.safe)
+                .map(|f| (idx, f.item)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let safe_idents = safe_fns
+        .iter()
+        .map(|(_, f)| f.sig.ident.clone())
+        .collect::<Vec<_>>();
+
+    let fns = items
+        .iter_mut()
+        .filter_map(|item| match item {
+            syn::ForeignItem::Fn(f) => Some((&mut f.attrs, &mut f.sig, f.vis.clone())),
+            _ => None,
+        })
+        .chain(
+            safe_fns
+                .iter_mut()
+                .map(|(_, f)| (&mut f.attrs, &mut f.sig, f.vis.clone())),
+        )
+        .collect();
+
+    let generated = annotated_fns(fns, &Default::default(), &opts, |sig, vis, fn_opts| {
+        if let Some(variadic) = &sig.variadic {
+            return Err(syn::Error::new_spanned(
+                variadic,
+                "Variadic functions are not supported",
+            ));
+        }
+
+        Ok(MacroItem {
+            unsafe_call: fn_opts.unsafe_call && !safe_idents.contains(&sig.ident),
+            ..MacroItem::new(
+                vis,
+                fn_opts.path.clone(),
+                sig.ident.clone(),
+                MacroType::Function,
+            )
+        })
+    });
+
+    for (idx, item) in safe_fns {
+        items[idx] = ForeignFn { safe: true, item }.into_foreign_item();
+    }
+
+    match generated {
+        Ok(generated) => ProcOutput {
+            modified: syn::ItemForeignMod { items, ..input }.to_token_stream(),
+            generated,
+        },
+        Err(e) => recover(syn::Item::ForeignMod(original), e),
+    }
+}
+
+/// Report a function inside an `extern` block that is annotated on its own.
+///
+/// A macro cannot be defined inside an `extern` block,
+/// so the function is only emitted with its helper attributes stripped.
+pub fn foreign_item_fn(mut input: ForeignFn) -> ProcOutput {
+    strip(&mut input.item.attrs);
+    strip_sig(&mut input.item.sig);
+
+    ProcOutput {
+        generated: syn::Error::new(
+            input.item.sig.ident.span(),
+            "Annotate the `extern` block instead, which selects the annotated functions inside it",
+        )
+        .to_compile_error(),
+        modified: input.to_token_stream(),
+    }
+}
+
+/// Foreign function, which is declared `safe` to call inside an `unsafe extern` block.
+///
+/// `syn` only parses `safe` functions as verbatim tokens.
+pub struct ForeignFn {
+    pub safe: bool,
+    pub item: syn::ForeignItemFn,
+}
+
+impl ForeignFn {
+    fn into_foreign_item(self) -> syn::ForeignItem {
+        match self.safe {
+            true => syn::ForeignItem::Verbatim(self.to_token_stream()),
+            false => syn::ForeignItem::Fn(self.item),
+        }
+    }
+}
+
+impl syn::parse::Parse for ForeignFn {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let safe = input
+            .fork()
+            .parse::<syn::Ident>()
+            .is_ok_and(|ident| ident == "safe");
+        if safe {
+            input.parse::<syn::Ident>()?;
+        }
 
+        let item = syn::ForeignItemFn {
+            attrs,
+            vis,
+            ..input.parse()?
+        };
+
+        Ok(Self { safe, item })
+    }
+}
+
+impl ToTokens for ForeignFn {
+    fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
+        let syn::ForeignItemFn {
+            attrs,
+            vis,
+            sig,
+            semi_token,
+        } = &self.item;
+
+        match self.safe {
+            true => tokens.extend(quote! {#(#attrs)* #vis safe #sig #semi_token}),
+            false => self.item.to_tokens(tokens),
+        }
+    }
+}
+
+/// The `unsafe` option is only allowed on unsafe functions,
+/// as the generated `unsafe` block would otherwise be unused.
+fn check_unsafe(sig: &syn::Signature, opts: &MacroOptions) -> Result<(), syn::Error> {
+    match (opts.unsafe_call, &sig.unsafety) {
+        (true, None) => Err(syn::Error::new(
+            sig.ident.span(),
+            "`unsafe` is only supported on unsafe and foreign functions",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Emit the item with all helper attributes stripped alongside the error,
+/// so that only the error is reported instead of every use of a missing item.
+pub fn recover(mut item: syn::Item, error: syn::Error) -> ProcOutput {
+    strip_item(&mut item);
+
+    ProcOutput {
+        modified: item.to_token_stream(),
+        generated: error.to_compile_error(),
+    }
+}
+
+/// Remove all helper attributes and annotations.
+fn strip(attrs: &mut Vec<syn::Attribute>) {
+    attrs.retain(|a| !crate::permute::is_helper_attr(a) && !is_defamed_attr(a));
+}
+
+/// Remove all helper attributes from the parameters of a function.
+fn strip_sig(sig: &mut syn::Signature) {
+    sig.inputs.iter_mut().for_each(|arg| match arg {
+        syn::FnArg::Receiver(r) => strip(&mut r.attrs),
+        syn::FnArg::Typed(t) => strip(&mut t.attrs),
+    });
+}
+
+/// Remove all helper attributes and nested annotations from an item.
+fn strip_item(item: &mut syn::Item) {
+    fn strip_fields(fields: &mut syn::Fields) {
+        fields.iter_mut().for_each(|f| strip(&mut f.attrs));
+    }
+
+    match item {
+        syn::Item::Fn(f) => strip_sig(&mut f.sig),
+        syn::Item::Struct(s) => strip_fields(&mut s.fields),
+        syn::Item::Enum(e) => e
+            .variants
+            .iter_mut()
+            .for_each(|v| strip_fields(&mut v.fields)),
+        syn::Item::Impl(i) => i.items.iter_mut().for_each(|item| {
+            if let syn::ImplItem::Fn(f) = item {
+                strip(&mut f.attrs);
+                strip_sig(&mut f.sig);
+            }
+        }),
+        syn::Item::Trait(t) => t.items.iter_mut().for_each(|item| {
+            if let syn::TraitItem::Fn(f) = item {
+                strip(&mut f.attrs);
+                strip_sig(&mut f.sig);
+            }
+        }),
+        syn::Item::ForeignMod(m) => m.items.iter_mut().for_each(|item| match item {
+            syn::ForeignItem::Fn(f) => {
+                strip(&mut f.attrs);
+                strip_sig(&mut f.sig);
+            }
+            syn::ForeignItem::Verbatim(tokens) => {
+                if let Ok(mut f) = syn::parse2::<ForeignFn>(tokens.clone()) {
+                    strip(&mut f.item.attrs);
+                    strip_sig(&mut f.item.sig);
+                    *tokens = f.to_token_stream();
+                }
+            }
+            _ => (),
+        }),
+        _ => (),
+    }
+}
+
+/// Combine the errors of both results, if any.
+fn both<A, B>(a: Result<A, syn::Error>, b: Result<B, syn::Error>) -> Result<(A, B), syn::Error> {
+    match (a, b) {
+        (Ok(a), Ok(b)) => Ok((a, b)),
+        (Err(e), Ok(_)) | (Ok(_), Err(e)) => Err(e),
+        (Err(mut e), Err(other)) => {
+            e.combine(other);
+            Err(e)
+        }
+    }
+}
+
+/// Generate the macro for a function signature.
+///
+/// Returns the signature with all helper attributes stripped, along with the generated macro.
+fn fn_signature(
+    sig: &syn::Signature,
+    parent: &syn::Generics,
+    opts: &MacroOptions,
+    mut item: MacroItem,
+) -> Result<(syn::Signature, pm2::TokenStream), syn::Error> {
     // check visibility vs provided path
-    match (&vis, fn_path.as_ref()) {
-        (syn::Visibility::Restricted(syn::VisRestricted { path, .. }), None) => {
-            if !path.is_ident("self") {
-                return syn::Error::new(
+    let path_check = check_relative_path(item.path.as_ref()).and_then(|_| {
+        match (&item.vis, item.path.as_ref()) {
+            (syn::Visibility::Restricted(syn::VisRestricted { path, .. }), None)
+                if !path.is_ident("self") =>
+            {
+                Err(syn::Error::new(
                     sig.ident.span(),
                     "Attribute requires a path to the function for public functions",
-                )
-                .to_compile_error()
-                .into();
+                ))
             }
-        }
-        (syn::Visibility::Public(_), None) => {
-            return syn::Error::new(
+            (syn::Visibility::Public(_), None) => Err(syn::Error::new(
                 sig.ident.span(),
                 "Attribute requires a path to the function for public functions",
-            )
-            .to_compile_error()
-            .into();
+            )),
+            _ => Ok(()),
         }
-        _ => (),
-    }
-
-    let params = match params::FunctionParams::from_punctuated(sig.inputs.clone()) {
-        Ok(p) => p,
-        Err(e) => return e.to_compile_error().into(),
-    };
+    });
 
-    if let Some(invalid) = params.first_invalid_param() {
-        return syn::Error::new(
-            invalid.inner_span(),
-            "Default parameters must be placed after all positional parameters",
-        )
-        .to_compile_error()
-        .into();
+    let (_, mut params) = both(
+        path_check,
+        params::FunctionParams::from_punctuated(sig.inputs.clone()),
+    )?;
+    if opts.option {
+        params.infer_optional();
     }
 
+    combine_errors(
+        [
+            params.first_invalid_param().map(|invalid| {
+                syn::Error::new(
+                    invalid.inner_span(),
+                    "Default parameters must be placed after all positional parameters",
+                )
+            }),
+            params.first_positional_after_kw().map(|invalid| {
+                syn::Error::new(
+                    invalid.inner_span(),
+                    format!(
+                        "Positional parameters must be placed before all #[{}] parameters",
+                        crate::KW_HELPER_ATTR
+                    ),
+                )
+            }),
+            params.first_pos_only_after_named().map(|invalid| {
+                syn::Error::new(
+                    invalid.inner_span(),
+                    format!(
+                        "#[{}] parameters must be placed before all other parameters",
+                        crate::POS_HELPER_ATTR
+                    ),
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten(),
+    )?;
+
+    let pos_only = params.pos_only_count();
+    let kw_only = params.kw_only_count();
+    let default_checks =
+        macro_gen::generate_default_checks(&merge_generics(parent, &sig.generics), &params.params);
     let params_inner = params.params.clone();
     let (positional, default) = {
         let partition = params_inner.iter().enumerate().find_map(|(idx, f)| {
-            if matches!(f.default_value, ParamAttr::Default | ParamAttr::Value(_)) {
+            if matches!(
+                f.default_value,
+                ParamAttr::Default(_) | ParamAttr::Value(_) | ParamAttr::Optional
+            ) {
                 Some(idx)
             } else {
                 None
@@ -114,12 +415,6 @@ pub fn item_fn(input: syn::ItemFn, fn_path: Option<syn::Path>) -> ProcOutput {
         }
     };
 
-    let permuted_new = crate::permute::permute(positional, default);
-    let permuted_concat = permuted_new
-        .into_iter()
-        .map(|permutation| [permutation.0, permutation.1].concat())
-        .collect::<Vec<_>>();
-
     // let permuted = params.permute_params();
     let new_args = params.to_punctuated();
     let mut new_sig = sig.clone();
@@ -131,74 +426,123 @@ pub fn item_fn(input: syn::ItemFn, fn_path: Option<syn::Path>) -> ProcOutput {
     //     .filter(|a| a.path().is_ident("doc"))
     //     .collect::<Vec<_>>();
 
-    let generated = macro_gen::generate_func_macro(
-        vis.clone(),
-        // doc_attrs,
-        // package_name,
-        fn_path,
-        new_sig.ident.clone(),
-        permuted_concat,
-        macro_gen::MacroType::Function,
+    item.receiver = params.receiver_doc_info();
+    item.rest = params.rest.as_ref().map(|rest| macro_gen::RestParam {
+        doc: rest.to_doc_info(),
+        collected: rest.rest_collect(quote! {$($__rest),*}),
+        empty: rest.rest_collect(quote! {}),
+    });
+
+    let strategy = Strategy::select(
+        opts.strategy,
+        crate::permute::permute_count(positional.len(), default.len(), pos_only, kw_only)
+            .saturating_mul(params.alias_variants()),
     );
 
-    let mod_fn = syn::ItemFn {
-        attrs,
-        vis,
-        sig: new_sig,
-        block,
-    }
-    .to_token_stream();
+    let partial = match opts.partial {
+        true => macro_gen::generate_partial_macro(item.clone(), params.params.clone())?,
+        false => pm2::TokenStream::new(),
+    };
+    let signature = match opts.signature {
+        true => macro_gen::generate_signature_const(&item, &params.params),
+        false => pm2::TokenStream::new(),
+    };
 
-    ProcOutput {
-        modified: mod_fn,
-        generated,
-    }
+    let generated = match strategy {
+        Strategy::Permute => {
+            let permuted_new = crate::permute::permute(positional, default, pos_only, kw_only);
+            let permuted_concat = permuted_new
+                .into_iter()
+                .map(|permutation| [permutation.0, permutation.1].concat())
+                .collect::<Vec<_>>();
+
+            macro_gen::generate_func_macro(item, permuted_concat)?
+        }
+        Strategy::Linear => macro_gen::generate_linear_macro(item, params.params)?,
+    };
+
+    Ok((
+        new_sig,
+        quote! {
+            #generated
+            #partial
+            #signature
+            #default_checks
+        },
+    ))
 }
 
 /// Process a struct definition
-pub fn item_struct(input: syn::ItemStruct, s_path: Option<syn::Path>) -> ProcOutput {
-    match input.fields {
-        syn::Fields::Named(named_fields) => item_struct_struct(
-            s_path,
-            input.attrs,
-            input.vis,
-            input.ident,
-            input.generics,
-            named_fields,
-        ),
-        syn::Fields::Unnamed(unnamed_fields) => item_struct_tuple(
-            s_path,
-            input.attrs,
-            input.vis,
-            input.ident,
-            input.generics,
-            unnamed_fields,
-        ),
-        syn::Fields::Unit => {
+pub fn item_struct(input: syn::ItemStruct, opts: MacroOptions) -> ProcOutput {
+    let relative = check_relative_path(opts.path.as_ref());
+
+    let processed = match &input.fields {
+        syn::Fields::Named(named_fields) if !named_fields.named.is_empty() => {
+            item_struct_struct(&opts, &input, named_fields)
+        }
+        syn::Fields::Unnamed(unnamed_fields) if !unnamed_fields.unnamed.is_empty() => {
+            item_struct_tuple(&opts, &input, unnamed_fields)
+        }
+        // structs without fields are constructed like unit structs
+        syn::Fields::Named(_) | syn::Fields::Unnamed(_) | syn::Fields::Unit => {
+            let unsupported = combine_errors(
+                [
+                    opts.partial
+                        .then_some("`partial` is only supported on functions and methods"),
+                    opts.unsafe_call
+                        .then_some("`unsafe` is only supported on unsafe and foreign functions"),
+                    opts.signature
+                        .then_some("`signature` is not supported on structs without fields"),
+                ]
+                .into_iter()
+                .flatten()
+                .map(|message| syn::Error::new(input.ident.span(), message)),
+            );
+            if let Err(e) = unsupported {
+                return recover(syn::Item::Struct(input), e);
+            }
+
             let warning = proc_macro_warning::FormattedWarning::new_deprecated(
                     "IrrelevantMacro",
                     "Remove this attribute macro. Unit structs do not contain any fields and cannot have default parameters.",
                     input.ident.span(),
                 );
 
-            quote! {
-                #warning
-            }
-            .into()
+            return ProcOutput {
+                modified: input.to_token_stream(),
+                generated: quote! {
+                    #warning
+                },
+            };
         }
+    };
+
+    match both(relative, processed) {
+        Ok((_, (fields, generated))) => ProcOutput {
+            modified: syn::ItemStruct { fields, ..input }.to_token_stream(),
+            generated,
+        },
+        Err(e) => recover(syn::Item::Struct(input), e),
     }
 }
 
 /// Process a normal struct
+///
+/// Returns the fields with all helper attributes stripped, along with the generated macro.
 fn item_struct_struct(
-    s_path: Option<syn::Path>,
-    attrs: Vec<syn::Attribute>,
-    vis: syn::Visibility,
-    ident: syn::Ident,
-    generics: syn::Generics,
-    fields: syn::FieldsNamed,
-) -> ProcOutput {
-    match (&vis, s_path.as_ref()) {
+    opts: &MacroOptions,
+    input: &syn::ItemStruct,
+    fields: &syn::FieldsNamed,
+) -> Result<(syn::Fields, pm2::TokenStream), syn::Error> {
+    let syn::ItemStruct {
+        vis,
+        ident,
+        generics,
+        ..
+    } = input;
+    let s_path = opts.path.clone();
+
+    let vis_check = match (vis, s_path.as_ref()) {
         (syn::Visibility::Restricted(syn::VisRestricted { path, .. }), p) => {
             if !fields.named.iter().all(|f| {
                 matches!(
@@ -206,21 +550,17 @@ fn item_struct_struct(
                     syn::Visibility::Public(_) | syn::Visibility::Restricted(_)
                 )
             }) {
-                return syn::Error::new(
+                Err(syn::Error::new(
                     ident.span(),
                     "Non-private structs must have non-private fields",
-                )
-                .to_compile_error()
-                .into();
-            }
-
-            if p.is_none() && !path.is_ident("self") {
-                return syn::Error::new(
+                ))
+            } else if p.is_none() && !path.is_ident("self") {
+                Err(syn::Error::new(
                     ident.span(),
                     "Attribute requires a path to the struct for public structs",
-                )
-                .to_compile_error()
-                .into();
+                ))
+            } else {
+                Ok(())
             }
         }
         (syn::Visibility::Public(_), p) => {
@@ -229,115 +569,51 @@ fn item_struct_struct(
                 .iter()
                 .all(|f| matches!(f.vis, syn::Visibility::Public(_)))
             {
-                return syn::Error::new(ident.span(), "Public structs must have public fields")
-                    .to_compile_error()
-                    .into();
-            }
-
-            if p.is_none() {
-                return syn::Error::new(
+                Err(syn::Error::new(
+                    ident.span(),
+                    "Public structs must have public fields",
+                ))
+            } else if p.is_none() {
+                Err(syn::Error::new(
                     ident.span(),
                     "Attribute requires a path to the struct for public structs",
-                )
-                .to_compile_error()
-                .into();
-            }
-        }
-        (syn::Visibility::Inherited, _) => (),
-    }
-
-    let n_fields = match StructFields::from_named(ident.clone(), fields.named.clone()) {
-        Ok(f) => f,
-        Err(e) => return e.to_compile_error().into(),
-    };
-
-    if let Some(invalid) = n_fields.first_invalid() {
-        return syn::Error::new(
-            invalid.ident.span(),
-            "Default parameters must be placed after all positional parameters",
-        )
-        .to_compile_error()
-        .into();
-    }
-
-    let stripped_fields = n_fields.strip_attributes();
-    let fields_inner = n_fields.fields;
-
-    let (positional, defaults) = {
-        let partition = fields_inner.iter().enumerate().find_map(|(idx, f)| {
-            if matches!(f.default_value, ParamAttr::Default | ParamAttr::Value(_)) {
-                Some(idx)
+                ))
             } else {
-                None
+                Ok(())
             }
-        });
-
-        match partition {
-            Some(p) => {
-                let tup = fields_inner.split_at(p);
-                (tup.0.to_vec(), tup.1.to_vec())
-            }
-            None => (fields_inner, vec![]),
         }
-
-        // (0,0)
+        (syn::Visibility::Inherited, _) => Ok(()),
     };
 
-    let permuted = crate::permute::permute(positional, defaults);
+    let (_, n_fields) = both(
+        vis_check,
+        StructFields::from_named(ident.clone(), fields.named.clone()),
+    )?;
 
-    let joined = permuted
-        .into_iter()
-        .map(|permutation| {
-            let has_missing = permutation
-                .1
-                .iter()
-                .any(|item| matches!(item, PermutedItem::Default(_)));
-
-            match has_missing {
-                true => [
-                    permutation.0,
-                    permutation.1,
-                    vec![PermutedItem::Default(StructField::dot_dot())],
-                ]
-                .concat(),
-                false => [permutation.0, permutation.1].concat(),
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let generated = macro_gen::generate_func_macro(
-        vis.clone(),
-        s_path.clone(),
-        ident.clone(),
-        joined,
-        MacroType::Struct,
-    );
+    let stripped_fields = n_fields.strip_attributes();
+    let item = MacroItem::new(vis.clone(), s_path, ident.clone(), MacroType::Struct);
+    let generated = fields_macro(n_fields, generics, opts, item)?;
 
-    ProcOutput {
-        modified: syn::ItemStruct {
-            attrs,
-            vis,
-            struct_token: Default::default(),
-            ident,
-            generics,
-            fields: stripped_fields,
-            semi_token: None,
-        }
-        .to_token_stream(),
-        generated,
-    }
+    Ok((stripped_fields, generated))
 }
 
 /// Process a tuple struct
+///
+/// Returns the fields with all helper attributes stripped, along with the generated macro.
 fn item_struct_tuple(
-    s_path: Option<syn::Path>,
-    attrs: Vec<syn::Attribute>,
-    vis: syn::Visibility,
-    ident: syn::Ident,
-    generics: syn::Generics,
-    fields: syn::FieldsUnnamed,
-) -> ProcOutput {
-    match (&vis, s_path.as_ref()) {
+    opts: &MacroOptions,
+    input: &syn::ItemStruct,
+    fields: &syn::FieldsUnnamed,
+) -> Result<(syn::Fields, pm2::TokenStream), syn::Error> {
+    let syn::ItemStruct {
+        vis,
+        ident,
+        generics,
+        ..
+    } = input;
+    let s_path = opts.path.clone();
+
+    let vis_check = match (vis, s_path.as_ref()) {
         (syn::Visibility::Restricted(syn::VisRestricted { path, .. }), p) => {
             if !fields.unnamed.iter().all(|f| {
                 matches!(
@@ -345,21 +621,17 @@ fn item_struct_tuple(
                     syn::Visibility::Public(_) | syn::Visibility::Restricted(_)
                 )
             }) {
-                return syn::Error::new(
+                Err(syn::Error::new(
                     ident.span(),
                     "Non-private struct tuples must have non-private items",
-                )
-                .to_compile_error()
-                .into();
-            }
-
-            if p.is_none() && !path.is_ident("self") {
-                return syn::Error::new(
+                ))
+            } else if p.is_none() && !path.is_ident("self") {
+                Err(syn::Error::new(
                     ident.span(),
                     "Attribute requires a path to the struct tuple for public structs",
-                )
-                .to_compile_error()
-                .into();
+                ))
+            } else {
+                Ok(())
             }
         }
         (syn::Visibility::Public(_), p) => {
@@ -368,46 +640,117 @@ fn item_struct_tuple(
                 .iter()
                 .all(|f| matches!(f.vis, syn::Visibility::Public(_)))
             {
-                return syn::Error::new(
+                Err(syn::Error::new(
                     ident.span(),
                     "Public struct tuples must have public items",
-                )
-                .to_compile_error()
-                .into();
-            }
-
-            if p.is_none() {
-                return syn::Error::new(
+                ))
+            } else if p.is_none() {
+                Err(syn::Error::new(
                     ident.span(),
                     "Attribute requires a path to the struct for public struct tuples",
-                )
-                .to_compile_error()
-                .into();
+                ))
+            } else {
+                Ok(())
             }
         }
-        (syn::Visibility::Inherited, _) => (),
-    }
-
-    let un_fields = match StructFields::from_unnamed(ident.clone(), fields.unnamed.clone()) {
-        Ok(un) => un,
-        Err(e) => return e.to_compile_error().into(),
+        (syn::Visibility::Inherited, _) => Ok(()),
     };
 
-    if let Some(invalid) = un_fields.first_invalid() {
-        return syn::Error::new(
-            invalid.ident.span(),
-            "Default parameters must be placed after all positional parameters",
-        )
-        .to_compile_error()
-        .into();
-    }
+    let (_, un_fields) = both(
+        vis_check,
+        StructFields::from_unnamed(ident.clone(), fields.unnamed.clone()),
+    )?;
 
     let stripped_fields = un_fields.strip_attributes();
-    let fields_inner = un_fields.fields;
+    let item = MacroItem::new(vis.clone(), s_path, ident.clone(), MacroType::StructTuple);
+    let generated = fields_macro(un_fields, generics, opts, item)?;
+
+    Ok((stripped_fields, generated))
+}
+
+/// Relative module paths would be resolved where the macro is invoked instead of where the item is defined,
+/// so module paths must start from the crate root.
+fn check_relative_path(path: Option<&syn::Path>) -> Result<(), syn::Error> {
+    match path {
+        Some(p) if macro_gen::is_relative_path(p) => Err(syn::Error::new_spanned(
+            p,
+            "Relative module paths are resolved where the macro is invoked. Use a `crate::` path instead",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Generate the macro for the fields of a struct or enum variant,
+/// along with the checks of their default values.
+fn fields_macro(
+    mut fields: StructFields,
+    generics: &syn::Generics,
+    opts: &MacroOptions,
+    item: MacroItem,
+) -> Result<pm2::TokenStream, syn::Error> {
+    if opts.option {
+        fields.infer_optional();
+    }
+
+    combine_errors(
+        [
+            opts.partial.then(|| {
+                syn::Error::new(
+                    item.ident.span(),
+                    "`partial` is only supported on functions and methods",
+                )
+            }),
+            opts.unsafe_call.then(|| {
+                syn::Error::new(
+                    item.ident.span(),
+                    "`unsafe` is only supported on unsafe and foreign functions",
+                )
+            }),
+            fields.first_invalid().map(|invalid| {
+                syn::Error::new(
+                    invalid.ident.span(),
+                    "Default parameters must be placed after all positional parameters",
+                )
+            }),
+            fields.first_positional_after_kw().map(|invalid| {
+                syn::Error::new(
+                    invalid.ident.span(),
+                    format!(
+                        "Positional fields must be placed before all #[{}] fields",
+                        crate::KW_HELPER_ATTR
+                    ),
+                )
+            }),
+            fields.first_pos_only_after_named().map(|invalid| {
+                syn::Error::new(
+                    invalid.ident.span(),
+                    format!(
+                        "#[{}] fields must be placed before all other fields",
+                        crate::POS_HELPER_ATTR
+                    ),
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten(),
+    )?;
+
+    let pos_only = fields.pos_only_count();
+    let kw_only = fields.kw_only_count();
+    let alias_variants = fields.alias_variants();
+    let default_checks = macro_gen::generate_default_checks(generics, &fields.fields);
+    let signature = match opts.signature {
+        true => macro_gen::generate_signature_const(&item, &fields.fields),
+        false => pm2::TokenStream::new(),
+    };
+    let fields_inner = fields.fields;
 
     let (positional, defaults) = {
         let partition = fields_inner.iter().enumerate().find_map(|(idx, f)| {
-            if matches!(f.default_value, ParamAttr::Default | ParamAttr::Value(_)) {
+            if matches!(
+                f.default_value,
+                ParamAttr::Default(_) | ParamAttr::Value(_) | ParamAttr::Optional
+            ) {
                 Some(idx)
             } else {
                 None
@@ -423,67 +766,471 @@ fn item_struct_tuple(
         }
     };
 
-    let permuted = crate::permute::permute_tuple_struct(positional, defaults);
+    let is_tuple = matches!(
+        item.output,
+        MacroType::StructTuple | MacroType::EnumVariantTuple
+    );
 
-    let generated = macro_gen::generate_func_macro(
-        vis.clone(),
-        s_path.clone(),
-        ident.clone(),
-        permuted,
-        MacroType::StructTuple,
+    let strategy = Strategy::select(
+        opts.strategy,
+        match is_tuple {
+            true => crate::permute::permute_tuple_struct_count(defaults.len()),
+            false => {
+                crate::permute::permute_count(positional.len(), defaults.len(), pos_only, kw_only)
+                    .saturating_mul(alias_variants)
+            }
+        },
     );
 
+    let generated = match (strategy, is_tuple) {
+        (Strategy::Permute, true) => macro_gen::generate_func_macro(
+            item,
+            crate::permute::permute_tuple_struct(positional, defaults),
+        )?,
+        (Strategy::Permute, false) => {
+            let permuted = crate::permute::permute(positional, defaults, pos_only, kw_only);
+
+            let joined = permuted
+                .into_iter()
+                .map(|permutation| {
+                    let has_missing = permutation
+                        .1
+                        .iter()
+                        .any(|item| matches!(item, PermutedItem::Default(_)));
+
+                    match has_missing {
+                        true => [
+                            permutation.0,
+                            permutation.1,
+                            vec![PermutedItem::Default(StructField::dot_dot())],
+                        ]
+                        .concat(),
+                        false => [permutation.0, permutation.1].concat(),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            macro_gen::generate_func_macro(item, joined)?
+        }
+        (Strategy::Linear, _) => {
+            macro_gen::generate_linear_macro(item, [positional, defaults].concat())?
+        }
+    };
+
+    Ok(quote! {
+        #generated
+        #signature
+        #default_checks
+    })
+}
+
+/// Process an enum definition.
+/// Each variant with fields is constructed by a macro named `Enum_Variant`.
+pub fn item_enum(input: syn::ItemEnum, opts: MacroOptions) -> ProcOutput {
+    let path_check = check_relative_path(opts.path.as_ref()).and_then(|_| {
+        match (&input.vis, opts.path.as_ref()) {
+            (syn::Visibility::Restricted(syn::VisRestricted { path, .. }), None)
+                if !path.is_ident("self") =>
+            {
+                Err(syn::Error::new(
+                    input.ident.span(),
+                    "Attribute requires a path to the enum for public enums",
+                ))
+            }
+            (syn::Visibility::Public(_), None) => Err(syn::Error::new(
+                input.ident.span(),
+                "Attribute requires a path to the enum for public enums",
+            )),
+            _ => Ok(()),
+        }
+    });
+
+    let enum_ident = &input.ident;
+    let mut generated = pm2::TokenStream::new();
+    let mut variants = input.variants.clone();
+    let mut errors = path_check.err().into_iter().collect::<Vec<_>>();
+
+    for variant in variants.iter_mut() {
+        let (fields, output) = match &variant.fields {
+            syn::Fields::Named(named) if !named.named.is_empty() => (
+                StructFields::from_named(variant.ident.clone(), named.named.clone()),
+                MacroType::EnumVariant,
+            ),
+            syn::Fields::Unnamed(unnamed) if !unnamed.unnamed.is_empty() => (
+                StructFields::from_unnamed(variant.ident.clone(), unnamed.unnamed.clone()),
+                MacroType::EnumVariantTuple,
+            ),
+            _ => continue,
+        };
+
+        let fields = match fields {
+            Ok(f) => f,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        let variant_ident = &variant.ident;
+        let item = MacroItem {
+            vis: input.vis.clone(),
+            path: opts.path.clone(),
+            ident: syn::Ident::new(
+                &format!("{}_{}", enum_ident.unraw(), variant_ident.unraw()),
+                variant_ident.span(),
+            ),
+            item: syn::parse_quote! {#enum_ident::#variant_ident},
+            receiver: None,
+            rest: None,
+            unsafe_call: false,
+            output,
+        };
+
+        variant.fields = fields.strip_attributes();
+
+        match fields_macro(fields, &input.generics, &opts, item) {
+            Ok(g) => generated.extend(g),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if let Err(e) = combine_errors(errors) {
+        return recover(syn::Item::Enum(input), e);
+    }
+
     ProcOutput {
-        modified: syn::ItemStruct {
-            attrs,
-            vis,
-            struct_token: Default::default(),
-            ident,
-            generics,
-            fields: stripped_fields,
-            semi_token: None,
+        modified: syn::ItemEnum { variants, ..input }.to_token_stream(),
+        generated,
+    }
+}
+
+/// Processes all functions inside an inherent `impl` block.
+pub fn item_impl(input: syn::ItemImpl, opts: MacroOptions) -> ProcOutput {
+    if let Some((_, trait_path, _)) = &input.trait_ {
+        let error = syn::Error::new_spanned(
+            trait_path,
+            "Trait implementations are not supported. Annotate the trait definition instead",
+        );
+        return recover(syn::Item::Impl(input), error);
+    }
+
+    let self_ident = match input.self_ty.as_ref() {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last(),
+        _ => None,
+    };
+    let self_ident = match self_ident {
+        Some(seg) => seg.ident.clone(),
+        None => {
+            let error = syn::Error::new_spanned(&input.self_ty, "Expected a type path");
+            return recover(syn::Item::Impl(input), error);
         }
-        .to_token_stream(),
+    };
+
+    let original = input.clone();
+    let mut items = input.items;
+    let fns = items
+        .iter_mut()
+        .filter_map(|item| match item {
+            syn::ImplItem::Fn(f) => Some((&mut f.attrs, &mut f.sig, f.vis.clone())),
+            _ => None,
+        })
+        .collect();
+
+    let generated = match assoc_fns(&self_ident, &input.generics, fns, &opts) {
+        Ok(g) => g,
+        Err(e) => return recover(syn::Item::Impl(original), e),
+    };
+
+    ProcOutput {
+        modified: syn::ItemImpl { items, ..input }.to_token_stream(),
         generated,
     }
 }
 
-#[allow(unused)]
-fn impl_item_fn(input: syn::ImplItemFn) {
-    // this is the only thing that is different from item_fn
-    let def_ness = input.defaultness;
+/// Processes all functions inside a `trait` definition.
+///
+/// Functions are called through the trait, so the implementation is selected
+/// by the receiver or inferred from the return type.
+/// Annotated functions are selected in the same way as [item_impl].
+pub fn item_trait(input: syn::ItemTrait, opts: MacroOptions) -> ProcOutput {
+    let original = input.clone();
+    let mut items = input.items;
+    let fns = items
+        .iter_mut()
+        .filter_map(|item| match item {
+            syn::TraitItem::Fn(f) => Some((&mut f.attrs, &mut f.sig, input.vis.clone())),
+            _ => None,
+        })
+        .collect();
 
-    let item_as_fn = syn::ItemFn {
-        attrs: input.attrs,
-        vis: input.vis,
-        sig: input.sig,
-        block: Box::new(input.block),
+    let generated = match assoc_fns(&input.ident, &input.generics, fns, &opts) {
+        Ok(g) => g,
+        Err(e) => return recover(syn::Item::Trait(original), e),
     };
+
+    ProcOutput {
+        modified: syn::ItemTrait { items, ..input }.to_token_stream(),
+        generated,
+    }
 }
 
-/// Processes all functions inside an `impl` block
-#[allow(unused)]
-pub fn item_impl(input: syn::ItemImpl) -> ProcOutput {
-    let inter = input.items.into_iter().map(|item| match item {
-        syn::ImplItem::Const(_) => todo!(),
-        syn::ImplItem::Fn(f) => {}
-        syn::ImplItem::Type(_) => todo!(),
-        syn::ImplItem::Macro(_) => todo!(),
-        syn::ImplItem::Verbatim(_) => todo!(),
-        _ => todo!(),
-    });
+/// Generate macros for associated functions of a type or trait, named `Parent_function`.
+fn assoc_fns(
+    parent: &syn::Ident,
+    generics: &syn::Generics,
+    fns: Vec<(
+        &mut Vec<syn::Attribute>,
+        &mut syn::Signature,
+        syn::Visibility,
+    )>,
+    opts: &MacroOptions,
+) -> Result<pm2::TokenStream, syn::Error> {
+    annotated_fns(fns, generics, opts, |sig, vis, fn_opts| {
+        check_unsafe(sig, fn_opts)?;
+
+        let fn_ident = &sig.ident;
+        Ok(MacroItem {
+            vis,
+            path: fn_opts.path.clone(),
+            ident: syn::Ident::new(
+                &format!("{}_{}", parent.unraw(), fn_ident.unraw()),
+                fn_ident.span(),
+            ),
+            item: syn::parse_quote! {#parent::#fn_ident},
+            receiver: None,
+            rest: None,
+            unsafe_call: fn_opts.unsafe_call,
+            output: MacroType::Method,
+        })
+    })
+}
+
+/// Generate macros for the functions of a block with the given generics, with the macro item of each function
+/// created by `macro_item` from its signature, visibility and options.
+///
+/// If any function is annotated with the attribute macro, only annotated functions are processed.
+/// Options on annotated functions override options on the parent item.
+/// Helper attributes and annotations are stripped in-place.
+fn annotated_fns(
+    fns: Vec<(
+        &mut Vec<syn::Attribute>,
+        &mut syn::Signature,
+        syn::Visibility,
+    )>,
+    generics: &syn::Generics,
+    opts: &MacroOptions,
+    macro_item: impl Fn(
+        &syn::Signature,
+        syn::Visibility,
+        &MacroOptions,
+    ) -> Result<MacroItem, syn::Error>,
+) -> Result<pm2::TokenStream, syn::Error> {
+    let any_marked = fns
+        .iter()
+        .any(|(attrs, _, _)| attrs.iter().any(is_defamed_attr));
+
+    let mut generated = pm2::TokenStream::new();
+    let mut errors = vec![];
+
+    for (attrs, sig, vis) in fns {
+        let marker = attrs.iter().position(is_defamed_attr);
+        let fn_opts = match marker.map(|idx| attrs.remove(idx)) {
+            Some(attr) => match method_options(&attr, opts) {
+                Ok(o) => o,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            },
+            None if any_marked => continue,
+            None => opts.clone(),
+        };
+
+        let expanded = macro_item(sig, vis, &fn_opts)
+            .and_then(|macro_item| fn_signature(sig, generics, &fn_opts, macro_item));
+
+        match expanded {
+            Ok((new_sig, method_macro)) => {
+                *sig = new_sig;
+                generated.extend(method_macro);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    combine_errors(errors)?;
+
+    Ok(generated)
+}
+
+/// Generics of a function, preceded by the generics of its parent `impl` or `trait` block.
+fn merge_generics(parent: &syn::Generics, generics: &syn::Generics) -> syn::Generics {
+    let mut params = parent
+        .params
+        .iter()
+        .chain(&generics.params)
+        .cloned()
+        .collect::<Vec<_>>();
+    // lifetimes must be declared before other generic parameters
+    params.sort_by_key(|g| !matches!(g, syn::GenericParam::Lifetime(_)));
+
+    let predicates = [&parent.where_clause, &generics.where_clause]
+        .into_iter()
+        .flatten()
+        .flat_map(|w| w.predicates.iter().cloned())
+        .collect::<syn::punctuated::Punctuated<_, syn::Token![,]>>();
+
+    syn::Generics {
+        params: params.into_iter().collect(),
+        where_clause: (!predicates.is_empty()).then(|| syn::WhereClause {
+            where_token: Default::default(),
+            predicates,
+        }),
+        ..generics.clone()
+    }
+}
+
+/// Returns `true` if the attribute is this attribute macro.
+fn is_defamed_attr(attr: &syn::Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == crate::ATTR_MACRO_IDENT)
+}
+
+/// Parse options on an annotated function, inheriting unset options from the parent item.
+fn method_options(
+    attr: &syn::Attribute,
+    parent: &MacroOptions,
+) -> Result<MacroOptions, syn::Error> {
+    let opts = match &attr.meta {
+        syn::Meta::Path(_) => MacroOptions::default(),
+        syn::Meta::List(l) => l.parse_args::<MacroOptions>()?,
+        syn::Meta::NameValue(nv) => {
+            return Err(syn::Error::new_spanned(nv, "Expected a list of options"))
+        }
+    };
+
+    Ok(opts.inherit(parent))
+}
+
+/// Process all annotated items inside an inline module, including nested inline modules.
+/// The module path of each item is inferred from `parent`, the path to the parent module.
+pub fn item_mod(input: syn::ItemMod, parent: Option<syn::Path>) -> ProcOutput {
+    let path: syn::Path = match parent {
+        Some(p) => {
+            let ident = &input.ident;
+            syn::parse_quote! {#p::#ident}
+        }
+        None => input.ident.clone().into(),
+    };
+
+    let syn::ItemMod {
+        attrs,
+        vis,
+        unsafety,
+        mod_token,
+        ident,
+        content,
+        ..
+    } = input;
+
+    let Some((_, items)) = content else {
+        let error = syn::Error::new(
+            ident.span(),
+            "Only inline modules can be scoped. Annotate the items inside the module file instead",
+        );
+        return ProcOutput {
+            modified: quote! {
+                #(#attrs)*
+                #vis #unsafety #mod_token #ident;
+            },
+            generated: error.to_compile_error(),
+        };
+    };
+
+    let mut expanded = pm2::TokenStream::new();
+
+    for mut item in items {
+        if let syn::Item::Mod(m) = item {
+            match m.content.is_some() {
+                true => expanded.extend(pm2::TokenStream::from(item_mod(m, Some(path.clone())))),
+                false => expanded.extend(m.to_token_stream()),
+            }
+            continue;
+        }
+
+        let attr = item_attrs(&mut item).and_then(|attrs| {
+            attrs
+                .iter()
+                .position(is_defamed_attr)
+                .map(|idx| attrs.remove(idx))
+        });
+
+        let attr = match attr {
+            Some(a) => a,
+            None => {
+                expanded.extend(item.to_token_stream());
+                continue;
+            }
+        };
+
+        let opts = match scoped_options(&attr, &path) {
+            Ok(o) => o,
+            Err(e) => {
+                expanded.extend(pm2::TokenStream::from(recover(item, e)));
+                continue;
+            }
+        };
 
-    todo!()
+        expanded.extend(pm2::TokenStream::from(crate::expand_item(
+            item.to_token_stream().into(),
+            opts,
+        )));
+    }
+
+    quote! {
+        #(#attrs)*
+        #vis #unsafety #mod_token #ident {
+            #expanded
+        }
+    }
+    .into()
+}
+
+/// Attributes of items that can be annotated.
+fn item_attrs(item: &mut syn::Item) -> Option<&mut Vec<syn::Attribute>> {
+    match item {
+        syn::Item::Struct(s) => Some(&mut s.attrs),
+        syn::Item::Fn(f) => Some(&mut f.attrs),
+        syn::Item::Impl(i) => Some(&mut i.attrs),
+        syn::Item::Trait(t) => Some(&mut t.attrs),
+        syn::Item::Enum(e) => Some(&mut e.attrs),
+        syn::Item::ForeignMod(m) => Some(&mut m.attrs),
+        _ => None,
+    }
 }
 
-#[allow(dead_code)]
-pub fn item_mod(_input: syn::ItemMod) -> ProcOutput {
-    todo!()
+/// Parse options of an item inside a scoped module. The module path is always inferred.
+fn scoped_options(attr: &syn::Attribute, path: &syn::Path) -> Result<MacroOptions, syn::Error> {
+    let opts = method_options(attr, &MacroOptions::default())?;
+
+    match opts.path {
+        Some(p) => Err(syn::Error::new_spanned(
+            p,
+            "The module path is inferred inside a scoped module and must be omitted",
+        )),
+        None => Ok(MacroOptions {
+            path: Some(path.clone()),
+            ..opts
+        }),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use quote::quote;
+    use proc_macro2 as pm2;
+    use quote::{quote, ToTokens};
 
     #[test]
     fn test_match_impl_block() {
@@ -498,6 +1245,197 @@ mod tests {
         let _: syn::ItemImpl = syn::parse2(tokens).unwrap();
     }
 
+    #[test]
+    fn test_check_relative_path() {
+        let check = |path: pm2::TokenStream| {
+            let path: syn::Path = syn::parse2(path).unwrap();
+
+            super::check_relative_path(Some(&path))
+        };
+
+        assert!(check(quote! {a::b}).is_ok());
+        assert!(check(quote! {crate::a}).is_ok());
+        assert!(check(quote! {super::storage}).is_err());
+        assert!(check(quote! {super}).is_err());
+        assert!(check(quote! {self}).is_err());
+        assert!(super::check_relative_path(None).is_ok());
+    }
+
+    #[test]
+    fn test_scoped_options() {
+        let path: syn::Path = syn::parse2(quote! {outer::inner}).unwrap();
+
+        let inferred: syn::Attribute = syn::parse_quote! {#[defamed(strategy = linear)]};
+        let opts = super::scoped_options(&inferred, &path).unwrap();
+        assert_eq!(
+            opts.path.to_token_stream().to_string(),
+            path.to_token_stream().to_string()
+        );
+        assert_eq!(opts.strategy, Some(crate::options::Strategy::Linear));
+
+        let explicit: syn::Attribute = syn::parse_quote! {#[defamed(outer)]};
+        assert!(super::scoped_options(&explicit, &path).is_err());
+    }
+
+    #[test]
+    fn test_method_options() {
+        let parent: crate::options::MacroOptions = syn::parse2(quote! {crate}).unwrap();
+
+        let method: syn::ImplItemFn = syn::parse2(quote! {
+            #[defamed::defamed(strategy = linear)]
+            fn method(&self) {}
+        })
+        .unwrap();
+
+        let attr = &method.attrs[0];
+        assert!(super::is_defamed_attr(attr));
+
+        let opts = super::method_options(attr, &parent).unwrap();
+        assert!(opts.path.unwrap().is_ident("crate"));
+        assert_eq!(opts.strategy, Some(crate::options::Strategy::Linear));
+    }
+
+    #[test]
+    fn test_recover() {
+        let input: syn::ItemFn = syn::parse2(quote! {
+            pub fn ordered(#[def] a: u8, b: u8, #[def] c: u8) -> u8 {
+                a + b + c
+            }
+        })
+        .unwrap();
+        let opts: crate::options::MacroOptions = syn::parse2(quote! {crate}).unwrap();
+
+        let output = super::item_fn(input, opts);
+        let expected = quote! {
+            pub fn ordered(a: u8, b: u8, c: u8) -> u8 {
+                a + b + c
+            }
+        };
+        assert_eq!(output.modified.to_string(), expected.to_string());
+        assert!(output.generated.to_string().contains("compile_error"));
+    }
+
+    #[test]
+    fn test_combined_errors() {
+        let input: syn::ItemStruct = syn::parse2(quote! {
+            struct Point {
+                #[rest]
+                x: Vec<u8>,
+                y: u8,
+                #[rest]
+                z: Vec<u8>,
+            }
+        })
+        .unwrap();
+        let opts: crate::options::MacroOptions = syn::parse2(quote! {}).unwrap();
+
+        let output = super::item_struct(input, opts);
+        let errors = output
+            .generated
+            .to_string()
+            .matches("compile_error")
+            .count();
+        assert_eq!(errors, 2, "{}", output.generated);
+        assert!(!output.modified.to_string().contains("rest"));
+    }
+
+    #[test]
+    fn test_unit_struct_options() {
+        let input: syn::ItemStruct = syn::parse2(quote! {struct Marker;}).unwrap();
+        let opts: crate::options::MacroOptions =
+            syn::parse2(quote! {crate, partial, signature}).unwrap();
+
+        let output = super::item_struct(input, opts);
+        let errors = output
+            .generated
+            .to_string()
+            .matches("compile_error")
+            .count();
+        assert_eq!(errors, 2, "{}", output.generated);
+
+        let input: syn::ItemStruct = syn::parse2(quote! {struct Empty {}}).unwrap();
+        let opts: crate::options::MacroOptions = syn::parse2(quote! {}).unwrap();
+        let output = super::item_struct(input, opts);
+        assert!(!output.generated.to_string().contains("compile_error"));
+    }
+
+    #[test]
+    fn test_foreign_mod() {
+        let input: syn::ItemForeignMod = syn::parse2(quote! {
+            extern "C" {
+                fn abs(#[def] x: i32) -> i32;
+                fn printf(#[def] format: *const u8, ...) -> i32;
+            }
+        })
+        .unwrap();
+        let opts: crate::options::MacroOptions = syn::parse2(quote! {unsafe}).unwrap();
+
+        let output = super::item_foreign_mod(input, opts);
+        let expected = quote! {
+            extern "C" {
+                fn abs(x: i32) -> i32;
+                fn printf(format: *const u8, ...) -> i32;
+            }
+        };
+        assert_eq!(output.modified.to_string(), expected.to_string());
+        assert!(output.generated.to_string().contains("Variadic"));
+
+        // safe functions do not need an `unsafe` block
+        let input: syn::ItemFn = syn::parse2(quote! {
+            fn safe(#[def] x: i32) -> i32 { x }
+        })
+        .unwrap();
+        let opts: crate::options::MacroOptions = syn::parse2(quote! {unsafe}).unwrap();
+        let output = super::item_fn(input, opts);
+        assert!(output.generated.to_string().contains("compile_error"));
+    }
+
+    #[test]
+    fn test_foreign_item_fn() {
+        // functions inside an `extern` block cannot define a macro
+        let input: super::ForeignFn = syn::parse2(quote! {
+            safe fn abs(#[def(-1)] x: i32) -> i32;
+        })
+        .unwrap();
+        assert!(input.safe);
+        let output = super::foreign_item_fn(input);
+        let expected = quote! {
+            safe fn abs(x: i32) -> i32;
+        };
+        assert_eq!(output.modified.to_string(), expected.to_string());
+        assert!(output
+            .generated
+            .to_string()
+            .contains("Annotate the `extern` block"));
+    }
+
+    #[test]
+    fn test_foreign_mod_safe_fn() {
+        let input: syn::ItemForeignMod = syn::parse2(quote! {
+            unsafe extern "C" {
+                safe fn abs(#[def(-1)] x: i32) -> i32;
+                fn labs(#[def(-1)] x: i64) -> i64;
+            }
+        })
+        .unwrap();
+        let opts: crate::options::MacroOptions = syn::parse2(quote! {unsafe}).unwrap();
+
+        let output = super::item_foreign_mod(input, opts);
+        let expected = quote! {
+            unsafe extern "C" {
+                safe fn abs(x: i32) -> i32;
+                fn labs(x: i64) -> i64;
+            }
+        };
+        assert_eq!(output.modified.to_string(), expected.to_string());
+
+        // only the call of `labs` is wrapped in an `unsafe` block
+        let generated = output.generated.to_string();
+        assert!(generated.contains("as abs ;"));
+        assert!(!generated.contains("unsafe { abs"));
+        assert!(generated.contains("unsafe { labs"));
+    }
+
     #[test]
     fn test_match_mod_block() {
         let tokens = quote! {
@@ -2,19 +2,47 @@
 
 mod block_logic;
 mod macro_gen;
+mod options;
 mod permute;
 mod traits;
 
 use proc_macro as pm;
 use proc_macro2 as pm2;
-use syn::spanned::Spanned;
 
 /// Identifier for public macros defined in the root module
 pub(crate) const ROOT_VISIBILITY_IDENT: &str = "crate";
 
+/// Name of this attribute macro, used to find annotated items inside other items
+pub(crate) const ATTR_MACRO_IDENT: &str = "defamed";
+
 /// "Helper" attribute for annotating function parameters
 pub(crate) const DEFAULT_HELPER_ATTR: &str = "def";
 
+/// "Helper" attribute for `Option<T>` parameters that default to `None`
+pub(crate) const OPTIONAL_HELPER_ATTR: &str = "opt";
+
+/// "Helper" attribute for the trailing parameter that collects all remaining positional arguments
+pub(crate) const REST_HELPER_ATTR: &str = "rest";
+
+/// "Helper" attribute for parameters that can only be passed by name
+pub(crate) const KW_HELPER_ATTR: &str = "kw";
+
+/// "Helper" attribute for parameters that can only be passed by position
+pub(crate) const POS_HELPER_ATTR: &str = "pos";
+
+/// "Helper" attribute for alternative names of a parameter
+pub(crate) const ALIAS_HELPER_ATTR: &str = "alias";
+
+/// "Helper" attribute for alternative names of a parameter that emit a deprecation warning
+pub(crate) const DEPRECATED_NAME_HELPER_ATTR: &str = "deprecated_name";
+
+/// "Helper" attribute for the name of a parameter with a destructuring pattern
+pub(crate) const NAME_HELPER_ATTR: &str = "name";
+
+/// Items whose permuted macro would contain more match arms than this
+/// use the linear strategy instead, unless a strategy is explicitly selected.
+pub(crate) const MAX_PERMUTED_ARMS: usize = 512;
+
 /// Attempt to parse multiple items at once, returning a tuple of results.
 macro_rules! syn_parses {
     ($item: expr, $($id: path),+) => {
@@ -77,38 +105,91 @@ macro_rules! syn_parses {
 /// ```
 #[proc_macro_attribute]
 pub fn defamed(attrs: pm::TokenStream, input: pm::TokenStream) -> pm::TokenStream {
-    let item_path = match attrs.is_empty() {
-        true => None,
-        false => {
-            // asd
-            let ex: syn::Expr = syn::parse_macro_input!(attrs);
-
-            if let syn::Expr::Path(syn::ExprPath { path, .. }) = &ex {
-                // if let syn::Expr::Path(p) = expr.as_ref() {
-                Some(path.clone())
-
-                // } else {
-            } else {
-                return syn::Error::new(ex.span(), "Expected path expression")
-                    .to_compile_error()
-                    .into();
+    match syn::parse::<options::MacroOptions>(attrs) {
+        Ok(opts) => expand_item(input, opts).into(),
+        Err(e) => match syn::parse::<syn::Item>(input.clone()) {
+            Ok(item) => block_logic::recover(item, e).into(),
+            Err(_) => block_logic::ProcOutput {
+                modified: input.into(),
+                generated: e.to_compile_error(),
             }
-        }
+            .into(),
+        },
+    }
+}
+
+/// Infer the module path of all `#[defamed]` items inside a module.
+///
+/// Nested inline modules are walked as well, so the path argument of [macro@defamed] can be omitted.
+/// The module is assumed to be declared in the crate root,
+/// otherwise the path to its parent module is passed as an argument.
+///
+/// ## Example
+/// ```
+/// #[defamed::scope]
+/// pub mod shapes {
+///     #[defamed::defamed]
+///     pub fn area(width: u32, #[def(1)] height: u32) -> u32 {
+///         width * height
+///     }
+///
+///     pub mod solids {
+///         #[defamed::defamed]
+///         pub fn volume(width: u32, #[def(1)] height: u32, #[def(1)] depth: u32) -> u32 {
+///             width * height * depth
+///         }
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(shapes::area!(2), 2);
+/// assert_eq!(shapes::solids::volume!(2, depth = 3), 6);
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn scope(attrs: pm::TokenStream, input: pm::TokenStream) -> pm::TokenStream {
+    let parent = match attrs.is_empty() {
+        true => None,
+        false => Some(syn::parse_macro_input!(attrs with syn::Path::parse_mod_style)),
     };
+    let input = syn::parse_macro_input!(input as syn::ItemMod);
 
-    let (expected_str, parsed) = syn_parses!(input, syn::ItemStruct, syn::ItemFn);
+    block_logic::item_mod(input, parent).into()
+}
 
-    let res = match parsed {
-        (Ok(s), _) => block_logic::item_struct(s, item_path),
-        (_, Ok(f)) => block_logic::item_fn(f, item_path),
+/// Process a single annotated item.
+pub(crate) fn expand_item(
+    input: pm::TokenStream,
+    opts: options::MacroOptions,
+) -> block_logic::ProcOutput {
+    let (expected_str, parsed) = syn_parses!(
+        input,
+        syn::ItemStruct,
+        syn::ItemFn,
+        syn::ItemImpl,
+        syn::ItemTrait,
+        syn::ItemEnum,
+        syn::ItemForeignMod
+    );
 
-        _ => syn::Error::new(
-            pm2::Span::call_site(),
-            format!("Item not supported. Expected: {}", expected_str),
-        )
-        .to_compile_error()
-        .into(),
-    };
+    match parsed {
+        (Ok(s), _, _, _, _, _) => block_logic::item_struct(s, opts),
+        (_, Ok(f), _, _, _, _) => block_logic::item_fn(f, opts),
+        (_, _, Ok(i), _, _, _) => block_logic::item_impl(i, opts),
+        (_, _, _, Ok(t), _, _) => block_logic::item_trait(t, opts),
+        (_, _, _, _, Ok(e), _) => block_logic::item_enum(e, opts),
+        (_, _, _, _, _, Ok(m)) => block_logic::item_foreign_mod(m, opts),
 
-    res.into()
+        _ => match syn::parse::<block_logic::ForeignFn>(input.clone()) {
+            Ok(f) => block_logic::foreign_item_fn(f),
+            Err(_) => block_logic::ProcOutput {
+                modified: input.into(),
+                generated: syn::Error::new(
+                    pm2::Span::call_site(),
+                    format!("Item not supported. Expected: {}", expected_str),
+                )
+                .to_compile_error(),
+            },
+        },
+    }
 }
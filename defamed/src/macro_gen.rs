@@ -4,22 +4,28 @@ use std::fmt::{Debug, Display};
 
 use proc_macro2::{self as pm2, Span};
 use quote::{quote, ToTokens};
-use syn::{
-    punctuated::Punctuated,
-    token::{Comma, Semi},
-    Visibility,
-};
+use syn::{ext::IdentExt, punctuated::Punctuated, token::Comma, Visibility};
 
-use crate::traits::{ToDocInfo, ToMacroPattern};
+use crate::{
+    permute::collect_results,
+    permute::ParamAttr,
+    permute::PermutedItem,
+    traits::{DocInfo, ToDefaultCheck, ToDocInfo, ToMacroPattern, ToMacroSlot},
+};
 
 #[derive(Clone, Copy, Debug)]
 pub enum MacroType {
     Function,
+    /// Associated function or method inside an `impl` block
+    Method,
     /// Struct with named fields
     Struct,
     /// Tuple struct with unnamed fields
     StructTuple,
-    // Enum,
+    /// Enum variant with named fields
+    EnumVariant,
+    /// Enum variant with unnamed fields
+    EnumVariantTuple,
 }
 
 /// Converts `self` to doc item disambiguation prefix
@@ -27,6 +33,8 @@ impl Display for MacroType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let item = match self {
             MacroType::Function => "fn@",
+            // associated items are linked by their path
+            MacroType::Method | MacroType::EnumVariant | MacroType::EnumVariantTuple => "",
             MacroType::Struct => "struct@",
             MacroType::StructTuple => "struct@",
         };
@@ -35,97 +43,1769 @@ impl Display for MacroType {
     }
 }
 
+/// Item called by a generated macro
+#[derive(Clone)]
+pub struct MacroItem {
+    /// Visibility of the generated macro
+    pub vis: Visibility,
+    /// Module path to the item, relative to the crate root
+    pub path: Option<syn::Path>,
+    /// Name of the generated macro
+    pub ident: syn::Ident,
+    /// Path to the item inside its module, such as `function` or `Type::method`
+    pub item: syn::Path,
+    /// Method receiver, passed as the first positional argument
+    pub receiver: Option<DocInfo>,
+    /// Trailing parameter collecting all remaining positional arguments
+    pub rest: Option<RestParam>,
+    /// The call is wrapped in an `unsafe` block
+    pub unsafe_call: bool,
+    pub output: MacroType,
+}
+
+/// Trailing parameter collecting all remaining positional arguments
+#[derive(Clone)]
+pub struct RestParam {
+    pub doc: DocInfo,
+    /// Remaining arguments `$($__rest),*`, converted into the parameter type
+    pub collected: pm2::TokenStream,
+    /// No remaining arguments, converted into the parameter type
+    pub empty: pm2::TokenStream,
+}
+
+impl MacroItem {
+    /// A standalone item, wrapped by a macro of the same name.
+    pub fn new(
+        vis: Visibility,
+        path: Option<syn::Path>,
+        ident: syn::Ident,
+        output: MacroType,
+    ) -> Self {
+        Self {
+            vis,
+            path,
+            item: ident.clone().into(),
+            ident,
+            receiver: None,
+            rest: None,
+            unsafe_call: false,
+            output,
+        }
+    }
+
+    /// Path used to call the item from inside the macro.
+    fn call_path(&self) -> pm2::TokenStream {
+        let root = item_path_root(self.path.as_ref());
+        let item = &self.item;
+
+        quote! {#root #item}
+    }
+
+    /// Path used by the macro to invoke itself.
+    fn self_call(&self) -> pm2::TokenStream {
+        let root = item_path_root(self.path.as_ref());
+        let ident = &self.ident;
+
+        quote! {#root #ident!}
+    }
+
+    /// Call the item with a receiver, if any, followed by comma-separated arguments.
+    ///
+    /// If `collected` is `true`, the `$__rest` repetition is passed to the rest parameter.
+    fn call(&self, args: pm2::TokenStream, collected: bool) -> pm2::TokenStream {
+        self.call_with_receiver(quote! {$__self}, args, collected)
+    }
+
+    /// Call the item with the given receiver expression, ignored if the item has no receiver.
+    ///
+    /// Explicit generic arguments `$__gen`, such as `::<u8>`, follow the path to the item.
+    ///
+    /// If the call is unsafe, all other arguments must already be bound by [MacroItem::bound_call].
+    /// Remaining arguments are bound here, so that no argument is evaluated inside the `unsafe` block.
+    fn call_with_receiver(
+        &self,
+        receiver: pm2::TokenStream,
+        args: pm2::TokenStream,
+        collected: bool,
+    ) -> pm2::TokenStream {
+        let call_path = self.call_path();
+        let rest = self.rest.as_ref().map(|rest| match collected {
+            true => rest.collected.clone(),
+            false => rest.empty.clone(),
+        });
+        let (rest, rest_binding) = match (self.unsafe_call, rest) {
+            (true, Some(rest)) => (Some(quote! {__rest}), Some(rest)),
+            (_, rest) => (rest, None),
+        };
+        let args = [
+            self.receiver.as_ref().map(|_| receiver),
+            Some(args).filter(|a| !a.is_empty()),
+            rest,
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Punctuated<pm2::TokenStream, Comma>>();
+
+        let call = match self.output {
+            MacroType::Function
+            | MacroType::Method
+            | MacroType::StructTuple
+            | MacroType::EnumVariantTuple => {
+                quote! {#call_path $($__gen)* (#args)}
+            }
+            MacroType::Struct | MacroType::EnumVariant => quote! {#call_path $($__gen)* {#args}},
+        };
+
+        match (self.unsafe_call, rest_binding) {
+            (true, Some(rest)) => quote! {match #rest { __rest => unsafe { #call } }},
+            (true, None) => quote! {unsafe { #call }},
+            (false, _) => call,
+        }
+    }
+
+    /// Returns `true` if arguments are bound to locals by [MacroItem::bound_call],
+    /// either because default values refer to preceding parameters, or the call is unsafe.
+    fn binds_args<P: ToMacroSlot>(&self, params: &[P]) -> bool {
+        self.unsafe_call || has_dependent_defaults(params)
+    }
+
+    /// Call the item with values bound to locals in declaration order,
+    /// so that default values can refer to preceding parameters.
+    ///
+    /// The receiver expression is ignored if the item has no receiver.
+    ///
+    /// Bindings are made with nested `match` expressions, which keep temporaries alive until the call returns.
+    /// Parameters following the last one referred to by a default value are passed directly,
+    /// so that type errors still point at the argument of the caller.
+    /// If the call is unsafe, all parameters are bound.
+    ///
+    /// ```ignore
+    /// match $__self { __self => match $a_val { a => Type::method(__self, a, a * 2, $c_val) } }
+    /// ```
+    fn bound_call<P: ToMacroSlot>(
+        &self,
+        receiver_expr: pm2::TokenStream,
+        params: &[P],
+        values: Vec<pm2::TokenStream>,
+        collected: bool,
+    ) -> pm2::TokenStream {
+        let bound = match self.unsafe_call {
+            true => params.len(),
+            false => bound_params(params),
+        };
+        let args: Punctuated<pm2::TokenStream, Comma> = params
+            .iter()
+            .zip(&values)
+            .enumerate()
+            .map(|(idx, (p, value))| match idx < bound {
+                true => p.slot_bound_call(),
+                false => p.slot_call(value.clone()),
+            })
+            .collect();
+
+        let receiver = syn::Ident::new("__self", Span::call_site());
+        let call = self.call_with_receiver(
+            receiver.to_token_stream(),
+            args.to_token_stream(),
+            collected,
+        );
+
+        let bound = params
+            .iter()
+            .zip(values)
+            .take(bound)
+            .rev()
+            .fold(call, |inner, (p, value)| {
+                let binding = p.slot_binding();
+                quote! {match #value { #binding => #inner }}
+            });
+
+        match self.receiver {
+            Some(_) => quote! {match #receiver_expr { #receiver => #bound }},
+            None => bound,
+        }
+    }
+}
+
+/// Returns `true` if any default value refers to a preceding parameter.
+fn has_dependent_defaults<P: ToMacroSlot>(params: &[P]) -> bool {
+    bound_params(params) > 0
+}
+
+/// Number of leading parameters bound to locals by [MacroItem::bound_call],
+/// up to the last parameter referred to by a default value.
+fn bound_params<P: ToMacroSlot>(params: &[P]) -> usize {
+    fn refers_to(tokens: pm2::TokenStream, bindings: &[syn::Ident]) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            pm2::TokenTree::Ident(ident) => bindings.contains(&ident),
+            pm2::TokenTree::Group(group) => refers_to(group.stream(), bindings),
+            pm2::TokenTree::Punct(_) | pm2::TokenTree::Literal(_) => false,
+        })
+    }
+
+    let bindings = params.iter().map(|p| p.slot_binding()).collect::<Vec<_>>();
+
+    params
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, p)| p.slot_default().map(|default| (idx, default)))
+        .filter_map(|(idx, default)| {
+            bindings[..idx]
+                .iter()
+                .rposition(|binding| refers_to(default.clone(), std::slice::from_ref(binding)))
+        })
+        .map(|idx| idx + 1)
+        .max()
+        .unwrap_or_default()
+}
+
 /// Generate a macro with all permutations of positional, named and default parameters.
 /// The macro inherits all doc comments from the original function.
 ///
 /// This macro generates code that calls the actual function,
 /// while reorderng and substituting parameters as needed.
-pub fn generate_func_macro<P: ToMacroPattern + ToDocInfo + Clone + PartialEq + Debug>(
-    vis: Visibility,
+///
+/// Permutations are matched in order of their number of positional arguments.
+/// Arguments that match no permutation fall through to [check_arms],
+/// which reports invalid arguments with a precise message.
+pub fn generate_func_macro<P>(
+    item: MacroItem,
     // package_name: &str,
-    item_path: Option<syn::Path>,
-    item_ident: syn::Ident,
-    params: Vec<Vec<P>>,
-    output: MacroType,
-) -> pm2::TokenStream {
+    params: Vec<Vec<PermutedItem<P>>>,
+) -> Result<pm2::TokenStream, syn::Error>
+where
+    P: ToMacroSlot + ToDocInfo + Clone + PartialEq,
+    PermutedItem<P>: ToMacroPattern,
+{
     // first pattern contains the correct order of parameteres to call
     let first_ref = params
         .first()
         .cloned()
-        .expect("at least one match pattern expected");
+        .ok_or_else(|| syn::Error::new(item.ident.span(), "At least one match pattern expected"))?;
 
-    let func_path_root = item_path
-        .clone()
-        .map(|g| {
-            if g.is_ident(crate::ROOT_VISIBILITY_IDENT) {
-                quote! {$#g ::}
-            } else {
-                quote! {$crate :: #g ::}
+    let slots = first_ref
+        .iter()
+        .map(|p| p.inner().clone())
+        .collect::<Vec<_>>();
+    let dependent = item.binds_args(&slots);
+
+    // let package_ident = syn::Ident::new(&package_name.replace("-", "_"), Span::call_site());
+
+    let mut macro_matches = collect_results(
+        params
+            .into_iter()
+            .flat_map(expand_aliases)
+            .flat_map(|p| {
+                // remaining arguments are only collected after all parameters are passed by position
+                let collect_rest = match &item.rest {
+                    Some(_) => p.iter().all(|i| matches!(i, PermutedItem::Positional(_))),
+                    None => false,
+                };
+
+                [false, true]
+                    .into_iter()
+                    .filter(move |collected| !collected || collect_rest)
+                    .map(move |collected| (p.clone(), collected))
+            })
+            .map(|(p, collected)| {
+                let macro_signature = [
+                    item.receiver.as_ref().map(|_| quote! {$__self: expr}),
+                    Some(create_macro_signature(&p)).filter(|sig| !sig.is_empty()),
+                    collected.then(|| quote! {$($__rest: expr),+}),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Punctuated<pm2::TokenStream, Comma>>();
+
+                let call = match dependent {
+                    true => item.bound_call(
+                        quote! {$__self},
+                        &slots,
+                        create_func_call_values(&first_ref, &p)?,
+                        collected,
+                    ),
+                    false => item.call(
+                        create_func_call_signature(first_ref.as_slice(), &p)?,
+                        collected,
+                    ),
+                };
+                let call = with_warnings(
+                    p.iter().filter_map(|i| match i {
+                        PermutedItem::Named(n) => n.slot_warning(),
+                        PermutedItem::Positional(_) | PermutedItem::Default(_) => None,
+                    }),
+                    call,
+                );
+
+                let positional = p
+                    .iter()
+                    .filter(|i| matches!(i, PermutedItem::Positional(_)))
+                    .count();
+                let arm = quote! {
+                    (@__defamed_call [$($__gen:tt)*] #macro_signature) => {
+                        #call
+                    };
+                };
+
+                // quote! {
+                //     (#macro_signature) => {
+                //         #func_path_root #item_ident(#func_signature)
+                //     }
+                // }
+
+                // all arguments are positional once the remaining arguments are collected
+                match collected {
+                    true => Ok((positional + 1, arm)),
+                    false => Ok((positional, arm)),
+                }
+            }),
+    )?;
+
+    // named arguments are matched before an assignment expression is matched by position
+    macro_matches.sort_by_key(|(positional, _)| *positional);
+    let max_positional = macro_matches
+        .iter()
+        .map(|(positional, _)| *positional)
+        .max()
+        .unwrap_or_default();
+    let guard_arms = (0..max_positional)
+        .map(|idx| {
+            let guard = name_guard_arm(&item, idx);
+            let arms = macro_matches
+                .iter()
+                .filter(|(positional, _)| *positional == idx + 1)
+                .map(|(_, arm)| arm);
+
+            quote! {
+                #guard
+                #(#arms)*
             }
         })
-        .unwrap_or_default();
+        .collect::<pm2::TokenStream>();
+    let unguarded_arms = macro_matches
+        .iter()
+        .filter(|(positional, _)| *positional == 0)
+        .map(|(_, arm)| arm);
 
-    // let package_ident = syn::Ident::new(&package_name.replace("-", "_"), Span::call_site());
+    let _macro_mod = syn::Ident::new(
+        &format!("{}_macros", item.ident.to_token_stream()),
+        Span::call_site(),
+    );
 
-    let macro_matches: Punctuated<pm2::TokenStream, Semi> = params
-        .into_iter()
+    let self_call = item.self_call();
+    let check_arms = check_arms(&item, &slots)?;
+    let entry_arms = entry_arms(&item, |generics, gen| {
+        quote! {
+            (#generics $($__args:tt)*) => {
+                #self_call(@__defamed_call #gen $($__args)*)
+            };
+        }
+    });
+
+    Ok(wrap_macro(
+        &item,
+        &first_ref,
+        quote! {
+            #(#unguarded_arms)*
+            #guard_arms
+            #check_arms
+            #entry_arms
+        },
+    ))
+}
+
+/// Arm reached when a name is passed after `idx` positional arguments,
+/// but no permutation with fewer positional arguments matches.
+///
+/// Permutations with more positional arguments would otherwise accept `name = value` as an assignment expression,
+/// so the arguments are checked by [check_arms] instead.
+/// Struct fields are passed as `name: value`, which is never an expression.
+fn name_guard_arm(item: &MacroItem, idx: usize) -> pm2::TokenStream {
+    if !matches!(item.output, MacroType::Function | MacroType::Method) {
+        return quote! {};
+    }
+
+    let self_call = item.self_call();
+    let receiver = item.receiver.as_ref().map(|_| quote! {$__self:expr,});
+    let before = (0..idx)
+        .map(|i| syn::Ident::new(&format!("__p{}", i), Span::call_site()))
+        .collect::<Vec<_>>();
+
+    quote! {
+        (@__defamed_call [$($__gen:tt)*] #receiver #($#before:expr,)* $__name:ident = $($__rest:tt)*) => {
+            #self_call(@__defamed_check_start #($#before,)* $__name = $($__rest)*)
+        };
+    }
+}
+
+/// Generate a recursive tt-muncher macro with positional, named and default parameters.
+///
+/// Each argument is munched into its slot, one at a time, before the item is called once.
+/// The number of match arms grows linearly with the number of parameters.
+///
+/// Unlike [generate_func_macro], named parameters are accepted in any order.
+///
+/// ```ignore
+/// macro_rules! some_macro (
+///     // call once all arguments are munched; unfilled defaults are substituted
+///     (@__defamed $phase:tt [$a_val:tt] [$($b_val:tt)?] ; ) => { ... };
+///     // one arm per named parameter, in any phase
+///     (@__defamed $phase:tt [$($s0:tt)*] [] ; b = $b_val:expr $(, $($rest:tt)*)?) => { ... };
+///     // one arm per positional parameter, before any named parameter; none for keyword-only parameters
+///     (@__defamed pos [] [] ; $a_val:expr $(, $($rest:tt)*)?) => { ... };
+///     // entry point
+///     ($($args:tt)*) => { some_macro!(@__defamed pos [] [] ; $($args)*) };
+/// )
+/// ```
+pub fn generate_linear_macro<P>(
+    item: MacroItem,
+    params: Vec<P>,
+) -> Result<pm2::TokenStream, syn::Error>
+where
+    P: ToMacroSlot + ToDocInfo + Clone + Debug,
+    PermutedItem<P>: ToMacroPattern,
+{
+    let self_call = item.self_call();
+
+    // generic arguments and the receiver are carried in front of all slots
+    let muncher = Muncher {
+        item: &item,
+        params: &params,
+        prefix: quote! {@__defamed},
+        recv: match item.receiver {
+            Some(_) => (
+                quote! {[$($__gen:tt)*] [$__self:tt]},
+                quote! {[$($__gen)*] [$__self]},
+            ),
+            None => (quote! {[$($__gen:tt)*]}, quote! {[$($__gen)*]}),
+        },
+        ordered: false,
+    };
+    let recv_pattern = &muncher.recv.0;
+
+    let dependent = item.binds_args(&params);
+    let call_args = |defaults_required: bool| {
+        let (patterns, values): (Vec<_>, Vec<_>) = params
+            .iter()
+            .map(|p| {
+                let val = p.slot_metavar();
+                let supplied = p.slot_wrap(quote! {$#val});
+                match p.slot_default() {
+                    Some(default) if !defaults_required => (
+                        quote! {[$($#val:tt)?]},
+                        quote! {#self_call(@__defamed_or [$(#supplied)?] [#default])},
+                    ),
+                    _ => (quote! {[$#val:tt]}, supplied),
+                }
+            })
+            .unzip();
+
+        let call = |collected: bool| match dependent {
+            true => item.bound_call(quote! {$__self}, &params, values.clone(), collected),
+            false => {
+                let args: Punctuated<pm2::TokenStream, Comma> = params
+                    .iter()
+                    .zip(values.clone())
+                    .map(|(p, value)| p.slot_call(value))
+                    .collect();
+
+                item.call(args.to_token_stream(), collected)
+            }
+        };
+
+        (quote! {#(#patterns)*}, call(false), call(true))
+    };
+
+    let finish_arms = match item.output {
+        MacroType::Function
+        | MacroType::Method
+        | MacroType::StructTuple
+        | MacroType::EnumVariantTuple => {
+            let (patterns, call, _) = call_args(false);
+            quote! {
+                (@__defamed $__phase:tt #recv_pattern #patterns ; ) => { #call };
+            }
+        }
+        // omitted defaults must be acknowledged with struct update syntax
+        MacroType::Struct | MacroType::EnumVariant => {
+            let (patterns_all, call_all, _) = call_args(true);
+            let (patterns, call, _) = call_args(false);
+            quote! {
+                (@__defamed $__phase:tt #recv_pattern #patterns_all ; ) => { #call_all };
+                (@__defamed $__phase:tt #recv_pattern #patterns ; ..) => { #call };
+            }
+        }
+    };
+
+    let invalid = format!("invalid arguments\nexpected: {}", signature(&item, &params));
+    let end_error_arms = muncher.end_arms(quote! {compile_error!(#invalid)});
+    let named_arms = muncher.named_arms()?;
+    let name_error_arms = muncher.name_error_arms()?;
+    let positional_arms = muncher.positional_arms()?;
+    let error_arms = muncher.error_arms();
+
+    // remaining arguments are collected once all slots are filled by position
+    let rest_arm = match item.rest {
+        Some(_) => {
+            let (patterns_all, _, call_rest) = call_args(true);
+            quote! {
+                (@__defamed pos #recv_pattern #patterns_all ; $($__rest:expr),+ $(,)?) => { #call_rest };
+            }
+        }
+        None => quote! {},
+    };
+
+    let empty_slots = params.iter().map(|_| quote! {[]}).collect::<Vec<_>>();
+    let entry_arms = entry_arms(&item, |generics, gen| match item.receiver {
+        Some(_) => quote! {
+            (#generics $__self:expr $(, $($__args:tt)*)?) => {
+                #self_call(@__defamed pos #gen [$__self] #(#empty_slots)* ; $($($__args)*)?)
+            };
+        },
+        None => quote! {
+            (#generics $($__args:tt)*) => {
+                #self_call(@__defamed pos #gen #(#empty_slots)* ; $($__args)*)
+            };
+        },
+    });
+
+    let macro_matches = quote! {
+        (@__defamed_or [$__val:expr] [$($__default:tt)*]) => { $__val };
+        (@__defamed_or [] [$($__default:tt)*]) => { $($__default)* };
+        #finish_arms
+        #end_error_arms
+        #named_arms
+        #name_error_arms
+        #positional_arms
+        #rest_arm
+        #error_arms
+        #entry_arms
+    };
+
+    Ok(wrap_macro(&item, &params, macro_matches))
+}
+
+/// Generate a partial application macro, which returns a closure over all parameters that are not passed.
+///
+/// Arguments are munched into slots as in [generate_linear_macro].
+/// Each empty required slot is then replaced by a parameter of the closure, in declaration order.
+/// The receiver, if any, is always the first parameter of the closure.
+/// Arguments are evaluated once, before the closure is created,
+/// and a clone of each is passed on every call of the closure.
+///
+/// Default values and the receiver are only placed in the last arm,
+/// so that they can refer to the locals bound by the same expansion.
+///
+/// ```ignore
+/// macro_rules! some_macro_partial (
+///     // rotate each slot in declaration order, empty required slots become parameters
+///     (@__defamed_bind 0 [$($gen:tt)*] [$($params:tt)*] [$($lets:tt)*] [] $($slots:tt)*) => {
+///         some_macro_partial!(@__defamed_bind 1 [$($gen)*] [$($params)* a,] [$($lets)*] $($slots)* [a])
+///     };
+///     // supplied arguments are bound outside of the closure
+///     (@__defamed_bind 1 [$($gen:tt)*] [$($params:tt)*] [$($lets:tt)*] [$val:tt] $($slots:tt)*) => {
+///         some_macro_partial!(@__defamed_bind 2 [$($gen)*] [$($params)*] [$($lets)* let __bound1 = $val;] $($slots)* [__bound1.clone()])
+///     };
+///     // all slots are rotated
+///     (@__defamed_bind 2 [$($gen:tt)*] [$($params:tt)*] [$($lets:tt)*] [$($a:tt)*] [$($b:tt)*]) => {{
+///         $($lets)*
+///         move |$($params)*| some_fn$($gen)*($($a)*, some_macro_partial!(@__defamed_or [$($b)*] [0]))
+///     }};
+///     ...
+/// )
+/// ```
+pub fn generate_partial_macro<P>(
+    item: MacroItem,
+    params: Vec<P>,
+) -> Result<pm2::TokenStream, syn::Error>
+where
+    P: ToMacroSlot + ToDocInfo + Clone + Debug,
+    PermutedItem<P>: ToMacroPattern,
+{
+    let item = MacroItem {
+        ident: syn::Ident::new(
+            &format!("{}_partial", item.ident.unraw()),
+            item.ident.span(),
+        ),
+        ..item
+    };
+    let self_call = item.self_call();
+
+    // the receiver is always a parameter of the closure, so only generic arguments are carried in front of all slots
+    let muncher = Muncher {
+        item: &item,
+        params: &params,
+        prefix: quote! {@__defamed},
+        recv: (quote! {[$($__gen:tt)*]}, quote! {[$($__gen)*]}),
+        ordered: false,
+    };
+
+    let all = muncher.slots(|s| quote! {[$($#s:tt)*]});
+    let all_call = muncher.slots(|s| quote! {[$($#s)*]});
+    let finish_arm = quote! {
+        (@__defamed $__phase:tt [$($__gen:tt)*] #(#all)* ; ) => {
+            #self_call(@__defamed_bind 0 [$($__gen)*] [] [] #(#all_call)*)
+        };
+    };
+
+    let bind_arms = params
+        .iter()
+        .enumerate()
+        .map(|(idx, p)| {
+            let current = pm2::Literal::usize_unsuffixed(idx);
+            let next = pm2::Literal::usize_unsuffixed(idx + 1);
+            let supplied = p.slot_wrap(quote! {$__val});
+            // a local of this expansion, which is only visible to the tokens it emits
+            let bound = syn::Ident::new(&format!("__bound{}", idx), Span::call_site());
+            let empty = match p.slot_default() {
+                Some(_) => quote! {[$($__params)*] [$($__lets)*] $($__slots)* []},
+                None => {
+                    let binding = p.slot_binding();
+                    quote! {[$($__params)* #binding,] [$($__lets)*] $($__slots)* [#binding]}
+                }
+            };
+
+            quote! {
+                (@__defamed_bind #current [$($__gen:tt)*] [$($__params:tt)*] [$($__lets:tt)*] [$__val:tt] $($__slots:tt)*) => {
+                    #self_call(@__defamed_bind #next [$($__gen)*] [$($__params)*] [$($__lets)* let #bound = #supplied;] $($__slots)* [::core::clone::Clone::clone(&#bound)])
+                };
+                (@__defamed_bind #current [$($__gen:tt)*] [$($__params:tt)*] [$($__lets:tt)*] [] $($__slots:tt)*) => {
+                    #self_call(@__defamed_bind #next [$($__gen)*] #empty)
+                };
+            }
+        })
+        .collect::<pm2::TokenStream>();
+
+    let values = params
+        .iter()
+        .zip(muncher.slots(|s| quote! {$($#s)*}))
+        .map(|(p, value)| match p.slot_default() {
+            Some(default) => quote! {#self_call(@__defamed_or [#value] [#default])},
+            None => value,
+        })
+        .collect::<Vec<_>>();
+    let call = match item.binds_args(&params) {
+        true => item.bound_call(quote! {__self}, &params, values, false),
+        false => {
+            let args: Punctuated<pm2::TokenStream, Comma> = params
+                .iter()
+                .zip(values)
+                .map(|(p, value)| p.slot_call(value))
+                .collect();
+
+            item.call_with_receiver(quote! {__self}, args.to_token_stream(), false)
+        }
+    };
+    let count = pm2::Literal::usize_unsuffixed(params.len());
+    let receiver = item.receiver.as_ref().map(|_| quote! {__self,});
+    let resolved_arm = quote! {
+        (@__defamed_bind #count [$($__gen:tt)*] [$($__params:tt)*] [$($__lets:tt)*] #(#all)*) => {{
+            $($__lets)*
+            move |#receiver $($__params)*| #call
+        }};
+    };
+
+    let named_arms = muncher.named_arms()?;
+    let name_error_arms = muncher.name_error_arms()?;
+    let positional_arms = muncher.positional_arms()?;
+    let error_arms = muncher.error_arms();
+
+    let empty_slots = params.iter().map(|_| quote! {[]}).collect::<Vec<_>>();
+    let entry_arms = entry_arms(&item, |generics, gen| {
+        quote! {
+            (#generics $($__args:tt)*) => {
+                #self_call(@__defamed pos #gen #(#empty_slots)* ; $($__args)*)
+            };
+        }
+    });
+
+    let macro_matches = quote! {
+        (@__defamed_or [$__val:expr] [$($__default:tt)*]) => { $__val };
+        (@__defamed_or [] [$($__default:tt)*]) => { $($__default)* };
+        #bind_arms
+        #resolved_arm
+        #finish_arm
+        #named_arms
+        #name_error_arms
+        #positional_arms
+        #error_arms
+        #entry_arms
+    };
+
+    Ok(wrap_macro(&item, &params, macro_matches))
+}
+
+/// Generate a hidden function that type checks all default values where the item is defined,
+/// instead of wherever they are omitted.
+///
+/// The function takes all parameters, so that default values can refer to preceding parameters.
+/// Defaults of types referring to generic parameters, `Self` or `impl Trait` are only known where they are omitted, and are not checked.
+///
+/// `Self` and `self` are not available in a standalone function,
+/// so generic parameters, bounds and parameters referring to them are left out,
+/// along with any default referring to what was left out.
+///
+/// ```ignore
+/// const _: () = {
+///     fn __defamed_defaults(a: u8, b: u16) {
+///         let _: u16 = a as u16 * 2;
+///     }
+/// };
+/// ```
+pub fn generate_default_checks<P>(generics: &syn::Generics, params: &[P]) -> pm2::TokenStream
+where
+    P: ToDefaultCheck,
+{
+    fn mentions(tokens: pm2::TokenStream, idents: &[syn::Ident]) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            pm2::TokenTree::Ident(ident) => idents.contains(&ident),
+            pm2::TokenTree::Group(group) => mentions(group.stream(), idents),
+            pm2::TokenTree::Punct(_) | pm2::TokenTree::Literal(_) => false,
+        })
+    }
+
+    fn idents(tokens: pm2::TokenStream) -> Vec<syn::Ident> {
+        tokens
+            .into_iter()
+            .flat_map(|tt| match tt {
+                pm2::TokenTree::Ident(ident) => vec![ident],
+                pm2::TokenTree::Group(group) => idents(group.stream()),
+                pm2::TokenTree::Punct(_) | pm2::TokenTree::Literal(_) => vec![],
+            })
+            .collect()
+    }
+
+    let generic_idents = generics
+        .params
+        .iter()
+        .filter_map(|g| match g {
+            syn::GenericParam::Type(t) => Some(t.ident.clone()),
+            syn::GenericParam::Const(c) => Some(c.ident.clone()),
+            syn::GenericParam::Lifetime(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let param_bindings = params
+        .iter()
+        .map(|p| match syn::parse2::<syn::PatType>(p.check_param()) {
+            Ok(pat_type) => idents(pat_type.pat.to_token_stream()),
+            Err(_) => vec![],
+        })
+        .collect::<Vec<_>>();
+
+    // everything referring to `Self` or `self`, until nothing else refers to it
+    let mut excluded = vec![
+        syn::Ident::new("Self", Span::call_site()),
+        syn::Ident::new("self", Span::call_site()),
+    ];
+    loop {
+        let refers = generics
+            .params
+            .iter()
+            .filter(|g| mentions(g.to_token_stream(), &excluded))
+            .filter_map(|g| match g {
+                syn::GenericParam::Type(t) => Some(t.ident.clone()),
+                syn::GenericParam::Const(c) => Some(c.ident.clone()),
+                syn::GenericParam::Lifetime(_) => None,
+            })
+            .chain(
+                generics
+                    .where_clause
+                    .iter()
+                    .flat_map(|w| w.predicates.iter())
+                    .filter(|w| mentions(w.to_token_stream(), &excluded))
+                    .flat_map(|w| idents(w.to_token_stream()))
+                    .filter(|ident| generic_idents.contains(ident)),
+            )
+            .chain(
+                params
+                    .iter()
+                    .zip(&param_bindings)
+                    .filter(|(p, _)| mentions(p.check_type().to_token_stream(), &excluded))
+                    .flat_map(|(_, bindings)| bindings.clone()),
+            )
+            .filter(|ident| !excluded.contains(ident))
+            .collect::<Vec<_>>();
+
+        if refers.is_empty() {
+            break;
+        }
+        excluded.extend(refers);
+    }
+
+    fn inferred(tokens: pm2::TokenStream, generic_idents: &[syn::Ident]) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            pm2::TokenTree::Ident(ident) => {
+                ident == "Self" || ident == "impl" || generic_idents.contains(&ident)
+            }
+            pm2::TokenTree::Group(group) => inferred(group.stream(), generic_idents),
+            pm2::TokenTree::Punct(_) | pm2::TokenTree::Literal(_) => false,
+        })
+    }
+
+    let checks = params
+        .iter()
+        .filter(|p| !inferred(p.check_type().to_token_stream(), &generic_idents))
+        .filter_map(|p| {
+            let ty = p.check_type();
+
+            match p.check_default() {
+                ParamAttr::Value(expr) if mentions(expr.to_token_stream(), &excluded) => None,
+                ParamAttr::Value(expr) => Some(quote! {
+                    let _: #ty = #expr;
+                }),
+                ParamAttr::Default(_) => {
+                    let name = match p.check_name() {
+                        Some(name) => format!("`{}`", name),
+                        None => "tuple field".to_string(),
+                    };
+                    let message = format!(
+                        "{} is initialized with `#[{}]`, but `{{Self}}` does not implement `Default`",
+                        name,
+                        crate::DEFAULT_HELPER_ATTR
+                    );
+
+                    Some(quote! {{
+                        #[diagnostic::on_unimplemented(message = #message, label = "required by this default value")]
+                        trait DefaultValue {}
+                        impl<T: core::default::Default> DefaultValue for T {}
+                        fn default_value<T: DefaultValue>() {}
+
+                        default_value::<#ty>();
+                    }})
+                }
+                ParamAttr::None | ParamAttr::Optional => None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if checks.is_empty() {
+        return quote! {};
+    }
+
+    let params = params
+        .iter()
+        .filter(|p| !mentions(p.check_type().to_token_stream(), &excluded))
+        .map(|p| p.check_param());
+    let generics = syn::Generics {
+        params: generics
+            .params
+            .iter()
+            .filter(|g| !mentions(g.to_token_stream(), &excluded))
+            .filter(|g| match g {
+                syn::GenericParam::Type(t) => !excluded.contains(&t.ident),
+                syn::GenericParam::Const(c) => !excluded.contains(&c.ident),
+                syn::GenericParam::Lifetime(_) => true,
+            })
+            .cloned()
+            .collect(),
+        where_clause: generics.where_clause.as_ref().map(|w| syn::WhereClause {
+            predicates: w
+                .predicates
+                .iter()
+                .filter(|p| !mentions(p.to_token_stream(), &excluded))
+                .cloned()
+                .collect(),
+            ..w.clone()
+        }),
+        ..generics.clone()
+    };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #[doc(hidden)]
+        const _: () = {
+            #[allow(unused, clippy::all)]
+            fn __defamed_defaults #impl_generics (#(#params),*) #where_clause {
+                #(#checks)*
+            }
+        };
+    }
+}
+
+/// Fallback arms of a macro generated by [generate_func_macro],
+/// reached only when the arguments match none of the permutation arms prefixed with `@__defamed_call`.
+///
+/// Arguments are munched into slots by a [Muncher] to find the first invalid argument,
+/// which is reported with [compile_error].
+fn check_arms<P>(item: &MacroItem, params: &[P]) -> Result<pm2::TokenStream, syn::Error>
+where
+    P: ToMacroSlot + ToDocInfo + Clone,
+    PermutedItem<P>: ToMacroPattern,
+{
+    let self_call = item.self_call();
+    let muncher = Muncher {
+        item,
+        params,
+        prefix: quote! {@__defamed_check},
+        recv: (quote! {}, quote! {}),
+        ordered: true,
+    };
+
+    // all arguments are valid, but match no permutation
+    let invalid = format!("invalid arguments\nexpected: {}", signature(item, params));
+    let end_arms = muncher.end_arms(quote! {compile_error!(#invalid)});
+    let named_arms = muncher.named_arms()?;
+    let name_error_arms = muncher.name_error_arms()?;
+    let positional_arms = muncher.positional_arms()?;
+    let error_arms = muncher.error_arms();
+
+    let rest_arm = match item.rest {
+        Some(_) => {
+            let filled = muncher.slots(|s| quote! {[$#s:tt]});
+            let filled_call = muncher.slots(|s| quote! {[$#s]});
+            quote! {
+                (@__defamed_check pos #(#filled)* ; $($__rest:expr),+ $(,)?) => {
+                    #self_call(@__defamed_check pos #(#filled_call)* ; )
+                };
+            }
+        }
+        None => quote! {},
+    };
+
+    let empty_slots = params.iter().map(|_| quote! {[]}).collect::<Vec<_>>();
+    let receiver_error = format!(
+        "expected the receiver as the first argument\nexpected: {}",
+        signature(item, params)
+    );
+    let start_arms = match item.receiver {
+        Some(_) => quote! {
+            (@__defamed_call [$($__gen:tt)*] $__self:expr $(, $($__args:tt)*)?) => {
+                #self_call(@__defamed_check_start $($($__args)*)?)
+            };
+            (@__defamed_call $($__args:tt)*) => { compile_error!(#receiver_error) };
+        },
+        None => quote! {
+            (@__defamed_call [$($__gen:tt)*] $($__args:tt)*) => {
+                #self_call(@__defamed_check_start $($__args)*)
+            };
+        },
+    };
+
+    Ok(quote! {
+        #start_arms
+        (@__defamed_check_start $($__args:tt)*) => {
+            #self_call(@__defamed_check pos #(#empty_slots)* ; $($__args)*)
+        };
+        #end_arms
+        #named_arms
+        #name_error_arms
+        #positional_arms
+        #rest_arm
+        #error_arms
+    })
+}
+
+/// Entry arms of the macro, with and without explicit generic arguments.
+///
+/// Generic arguments are passed before all other arguments, as in `function!(::<u8>; a, b)`.
+/// They are munched one token at a time up to the closing `>;`,
+/// since a repetition of tokens cannot be followed by `>`.
+/// `entry` creates the arms that match all other arguments,
+/// given the leading tokens of their pattern and the generic arguments passed on, such as `[::<u8>]`.
+fn entry_arms<F>(item: &MacroItem, entry: F) -> pm2::TokenStream
+where
+    F: Fn(pm2::TokenStream, pm2::TokenStream) -> pm2::TokenStream,
+{
+    let self_call = item.self_call();
+    let explicit = entry(
+        quote! {@__defamed_generics [$($__gen:tt)*]},
+        quote! {[$($__gen)*]},
+    );
+    let inferred = entry(quote! {}, quote! {[]});
+    let invalid = format!(
+        "expected generic arguments followed by `;`, such as `{}!(::<T>; ...)`",
+        item.ident
+    );
+
+    // `>>` is a single token when a generic argument ends with a generic argument, such as `::<Vec<u8>>`
+    quote! {
+        (@__defamed_turbofish [$($__gen:tt)*] > ; $($__args:tt)*) => {
+            #self_call(@__defamed_generics [::<$($__gen)*>] $($__args)*)
+        };
+        (@__defamed_turbofish [$($__gen:tt)*] >> ; $($__args:tt)*) => {
+            #self_call(@__defamed_generics [::<$($__gen)* >>] $($__args)*)
+        };
+        (@__defamed_turbofish [$($__gen:tt)*] $__t:tt $($__rest:tt)*) => {
+            #self_call(@__defamed_turbofish [$($__gen)* $__t] $($__rest)*)
+        };
+        (@__defamed_turbofish [$($__gen:tt)*]) => { compile_error!(#invalid) };
+        (:: < $($__rest:tt)*) => { #self_call(@__defamed_turbofish [] $($__rest)*) };
+        #explicit
+        #inferred
+    }
+}
+
+/// Recursive tt-muncher arms over the slots of `params`.
+///
+/// The state of the muncher is `(#prefix $phase:tt #recv [slot]* ; arguments)`,
+/// where `$phase` is `pos` until the first named argument, then `named`.
+struct Muncher<'a, P> {
+    item: &'a MacroItem,
+    params: &'a [P],
+    /// Leading token of all arms, such as `@__defamed`
+    prefix: pm2::TokenStream,
+    /// Receiver in front of all slots, as a pattern and as passed to the recursive call
+    recv: (pm2::TokenStream, pm2::TokenStream),
+    /// Named required parameters must be passed before named parameters with default values,
+    /// as in the permutations of [generate_func_macro]
+    ordered: bool,
+}
+
+impl<P> Muncher<'_, P>
+where
+    P: ToMacroSlot + ToDocInfo + Clone,
+    PermutedItem<P>: ToMacroPattern,
+{
+    /// Map the state metavariable of each slot, such as `__s0`.
+    fn slots<F>(&self, f: F) -> Vec<pm2::TokenStream>
+    where
+        F: Fn(&syn::Ident) -> pm2::TokenStream,
+    {
+        (0..self.params.len())
+            .map(|idx| f(&syn::Ident::new(&format!("__s{}", idx), Span::call_site())))
+            .collect()
+    }
+
+    /// Slot patterns of all slots, with `filled` for required slots.
+    fn required_slots(&self, filled: bool) -> Vec<pm2::TokenStream> {
+        let any = self.slots(|s| quote! {[$($#s:tt)*]});
+        let some = self.slots(|s| quote! {[$($#s:tt)+]});
+
+        self.params
+            .iter()
+            .zip(any.into_iter().zip(some))
+            .map(|(p, (any, some))| match (filled, p.slot_default()) {
+                (true, None) => some,
+                _ => any,
+            })
+            .collect()
+    }
+
+    /// One arm per named parameter and alias, in any phase.
+    ///
+    /// If the muncher is `ordered`, required parameters are only accepted before all parameters with default values.
+    fn named_arms(&self) -> Result<pm2::TokenStream, syn::Error> {
+        let Self {
+            item,
+            params,
+            prefix,
+            recv: (recv_pattern, recv_call),
+            ordered,
+        } = self;
+        let self_call = item.self_call();
+
+        let pass_pattern = self.slots(|s| quote! {[$($#s:tt)*]});
+        let pass_call = self.slots(|s| quote! {[$($#s)*]});
+
+        params
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.slot_named())
+            .flat_map(|(idx, p)| {
+                std::iter::once(p.clone())
+                    .chain(p.slot_aliases())
+                    .map(move |p| (idx, p))
+            })
+            .map(|(idx, p)| {
+                let val = p.slot_metavar();
+                let required = p.slot_default().is_none();
+                let empty = |i: usize| *ordered && required && params[i].slot_default().is_some();
+                let slots_pat = (0..params.len()).map(|i| match i == idx || empty(i) {
+                    true => quote! {[]},
+                    false => pass_pattern[i].clone(),
+                });
+                let slots_call = (0..params.len()).map(|i| match (i == idx, empty(i)) {
+                    (true, _) => quote! {[$#val]},
+                    (false, true) => quote! {[]},
+                    (false, false) => pass_call[i].clone(),
+                });
+
+                let pattern = macro_pattern(PermutedItem::Named(p.clone()))?;
+
+                let munch = with_warnings(
+                    p.slot_warning(),
+                    quote! {#self_call(#prefix named #recv_call #(#slots_call)* ; $($($__rest)*)?)},
+                );
+
+                Ok(quote! {
+                    (#prefix $__phase:tt #recv_pattern #(#slots_pat)* ; #pattern $(, $($__rest:tt)*)?) => {
+                        #munch
+                    };
+                })
+            })
+            .collect()
+    }
+
+    /// One arm per positional parameter, in the `pos` phase.
+    fn positional_arms(&self) -> Result<pm2::TokenStream, syn::Error> {
+        let Self {
+            item,
+            params,
+            prefix,
+            recv: (recv_pattern, recv_call),
+            ..
+        } = self;
+        let self_call = item.self_call();
+
+        // positional slots are filled in order, so all preceding slots are filled and all following slots are empty
+        let filled_pattern = self.slots(|s| quote! {[$#s:tt]});
+        let filled_call = self.slots(|s| quote! {[$#s]});
+        params
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.slot_positional())
+            .map(|(idx, p)| {
+                let before_pat = &filled_pattern[..idx];
+                let before_call = &filled_call[..idx];
+                let after = (idx + 1..params.len()).map(|_| quote! {[]});
+                let after_call = after.clone();
+
+                let pattern = macro_pattern(PermutedItem::Positional(p.clone()))?;
+                let val = p.slot_metavar();
+
+                Ok(quote! {
+                    (#prefix pos #recv_pattern #(#before_pat)* [] #(#after)* ; #pattern $(, $($__rest:tt)*)?) => {
+                        #self_call(#prefix pos #recv_call #(#before_call)* [$#val] #(#after_call)* ; $($($__rest)*)?)
+                    };
+                })
+            })
+            .collect()
+    }
+
+    /// Arms reached once all arguments are munched.
+    ///
+    /// Missing required parameters are all reported at once. Otherwise, all arguments are valid and `valid` is expanded.
+    fn end_arms(&self, valid: pm2::TokenStream) -> pm2::TokenStream {
+        let Self {
+            item,
+            params,
+            prefix,
+            recv: (recv_pattern, _),
+            ..
+        } = self;
+        let self_call = item.self_call();
+        let any = any_value();
+
+        let omitted = format!(
+            "fields with default values can only be omitted with a trailing `..`\nexpected: {}",
+            signature(item, params)
+        );
+
+        let all = self.slots(|s| quote! {[$($#s:tt)*]});
+        let filled = self.slots(|s| quote! {[$($#s:tt)+]});
+        let required = self.required_slots(true);
+
+        let missing = params
+            .iter()
+            .zip(self.slots(|s| quote! {[$($#s)*]}))
+            .filter(|(p, _)| p.slot_default().is_none())
+            .map(|(p, slot)| {
+                let name = p.to_doc_info().ident;
+                quote! {#self_call(@__defamed_missing #name #slot);}
+            })
+            .collect::<Vec<_>>();
+        let missing_arms = match missing.is_empty() {
+            true => quote! {},
+            false => quote! {
+                (@__defamed_missing $__name:literal []) => {
+                    compile_error!(concat!("missing required parameter `", $__name, "`"))
+                };
+                (@__defamed_missing $__name:literal [$($__t:tt)+]) => {};
+            },
+        };
+
+        match item.output {
+            MacroType::Function
+            | MacroType::Method
+            | MacroType::StructTuple
+            | MacroType::EnumVariantTuple => quote! {
+                #missing_arms
+                (#prefix $__phase:tt #recv_pattern #(#required)* ; ) => { #valid };
+                (#prefix $__phase:tt #recv_pattern #(#all)* ; ) => {{ #(#missing)* #any }};
+            },
+            MacroType::Struct | MacroType::EnumVariant => quote! {
+                #missing_arms
+                (#prefix $__phase:tt #recv_pattern #(#required)* ; ..) => { #valid };
+                (#prefix $__phase:tt #recv_pattern #(#all)* ; ..) => {{ #(#missing)* #any }};
+                (#prefix $__phase:tt #recv_pattern #(#filled)* ; ) => { #valid };
+                (#prefix $__phase:tt #recv_pattern #(#required)* ; ) => { compile_error!(#omitted) };
+                (#prefix $__phase:tt #recv_pattern #(#all)* ; ) => {{ #(#missing)* #any }};
+            },
+        }
+    }
+
+    /// Arms reached when the next argument is passed by a name that cannot be munched.
+    ///
+    /// These precede the positional arms, which would otherwise accept `name = value` as an assignment expression.
+    fn name_error_arms(&self) -> Result<pm2::TokenStream, syn::Error> {
+        let Self {
+            item,
+            params,
+            prefix,
+            recv: (recv_pattern, _),
+            ordered,
+        } = self;
+
+        let expected = signature(item, params);
+        let all = self.slots(|s| quote! {[$($#s:tt)*]});
+        let filled = self.slots(|s| quote! {[$($#s:tt)+]});
+
+        // named arguments of tuple items are never accepted, parameter names are not known to the caller
+        match item.output {
+            MacroType::Function
+            | MacroType::Method
+            | MacroType::Struct
+            | MacroType::EnumVariant => {
+                let separator = match item.output {
+                    MacroType::Struct | MacroType::EnumVariant => quote! {:},
+                    _ => quote! {=},
+                };
+
+                let name_arms = params
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(idx, p)| {
+                        std::iter::once(p.clone())
+                            .chain(p.slot_aliases())
+                            .map(move |p| (idx, p))
+                    })
+                    .map(|(idx, p)| {
+                        let pattern = macro_pattern(PermutedItem::Named(p.clone()))?;
+                        let name = p.to_doc_info().ident;
+
+                        if !p.slot_named() {
+                            let message = format!(
+                                "parameter `{}` is positional-only and cannot be passed by name\nexpected: {}",
+                                name, expected
+                            );
+                            return Ok(quote! {
+                                (#prefix $__phase:tt #recv_pattern #(#all)* ; #pattern $(, $($__rest:tt)*)?) => {
+                                    compile_error!(#message)
+                                };
+                            });
+                        }
+
+                        let slots = all.iter().zip(&filled).enumerate().map(|(i, (any, filled))| match i == idx {
+                            true => filled.clone(),
+                            false => any.clone(),
+                        });
+                        let duplicate = duplicate_parameter(&name, &p.slot_metavar());
+
+                        // an empty slot is only rejected by an ordered muncher, after a parameter with a default value
+                        let order_arm = match *ordered && p.slot_default().is_none() {
+                            true => {
+                                let message = format!(
+                                    "required parameter `{}` must be passed before parameters with default values\nexpected: {}",
+                                    name, expected
+                                );
+                                quote! {
+                                    (#prefix $__phase:tt #recv_pattern #(#all)* ; #pattern $(, $($__rest:tt)*)?) => {
+                                        compile_error!(#message)
+                                    };
+                                }
+                            }
+                            false => quote! {},
+                        };
+
+                        Ok(quote! {
+                            (#prefix $__phase:tt #recv_pattern #(#slots)* ; #pattern $(, $($__rest:tt)*)?) => {
+                                #duplicate
+                            };
+                            #order_arm
+                        })
+                    })
+                    .collect::<Result<pm2::TokenStream, syn::Error>>()?;
+
+                let names = params
+                    .iter()
+                    .filter(|p| p.slot_named())
+                    .map(|p| p.to_doc_info().ident)
+                    .collect::<Vec<_>>();
+                let unknown = unknown_parameter(&names, &expected);
+                let unknown_arm = quote! {
+                    (#prefix $__phase:tt #recv_pattern #(#all)* ; $__name:ident #separator $__val:expr $(, $($__rest:tt)*)?) => {
+                        #unknown
+                    };
+                };
+
+                Ok(quote! {
+                    #name_arms
+                    #unknown_arm
+                })
+            }
+            MacroType::StructTuple | MacroType::EnumVariantTuple => Ok(quote! {}),
+        }
+    }
+
+    /// Arms reached when the next argument cannot be munched by position.
+    fn error_arms(&self) -> pm2::TokenStream {
+        let Self {
+            item,
+            params,
+            prefix,
+            recv: (recv_pattern, _),
+            ..
+        } = self;
+
+        let expected = signature(item, params);
+        let all = self.slots(|s| quote! {[$($#s:tt)*]});
+
+        let after_named = format!(
+            "positional arguments must be passed before named arguments\nexpected: {}",
+            expected
+        );
+        let too_many = format!("too many positional arguments\nexpected: {}", expected);
+        let invalid = format!("invalid arguments\nexpected: {}", expected);
+
+        quote! {
+            (#prefix pos #recv_pattern #(#all)* ; $__val:expr $(, $($__rest:tt)*)?) => {
+                compile_error!(#too_many)
+            };
+            (#prefix named #recv_pattern #(#all)* ; $__val:expr $(, $($__rest:tt)*)?) => {
+                compile_error!(#after_named)
+            };
+            (#prefix $($__rest:tt)*) => { compile_error!(#invalid) };
+        }
+    }
+}
+
+/// Accepted arguments of the macro, such as `function!(a: i32, b: bool = true)`.
+fn signature<P: ToDocInfo>(item: &MacroItem, params: &[P]) -> String {
+    let tuple = matches!(
+        item.output,
+        MacroType::StructTuple | MacroType::EnumVariantTuple
+    );
+
+    let docs = params.iter().map(|p| p.to_doc_info()).collect::<Vec<_>>();
+    let omittable = docs.iter().any(|p| p.default_value.is_some());
+
+    let args = item
+        .receiver
+        .iter()
+        .cloned()
+        .chain(docs)
         .map(|p| {
-            let macro_signature = create_macro_signature(&p);
-            let func_signature = create_func_call_signature(first_ref.as_slice(), &p);
+            let arg = match tuple {
+                true => p.ty,
+                false => format!("{}: {}", p.ident, p.ty),
+            };
+
+            match p.default_value {
+                Some(default) => format!("{} = {}", arg, default),
+                None => arg,
+            }
+        })
+        .chain(
+            item.rest
+                .iter()
+                .map(|rest| format!("{}: {}...", rest.doc.ident, rest.doc.ty)),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match item.output {
+        MacroType::Struct | MacroType::EnumVariant if omittable => {
+            format!("{}! {{ {}, .. }}", item.ident, args)
+        }
+        MacroType::Struct | MacroType::EnumVariant => format!("{}! {{ {} }}", item.ident, args),
+        MacroType::Function
+        | MacroType::Method
+        | MacroType::StructTuple
+        | MacroType::EnumVariantTuple => format!("{}!({})", item.ident, args),
+    }
+}
+
+/// Report a parameter passed more than once at the span of its value `$val`.
+///
+/// [compile_error] can only point at the macro invocation,
+/// so the value is passed to a function bound by a [diagnostic_trait],
+/// which names the parameter by a unit struct.
+fn duplicate_parameter(name: &str, val: &syn::Ident) -> pm2::TokenStream {
+    let any = any_value();
+    let message = format!("parameter `{}` specified more than once", name);
+    let label = format!("`{}` is already passed", name);
+    let passed_once = diagnostic_trait(
+        &syn::Ident::new("PassedOnce", Span::call_site()),
+        &message,
+        &label,
+        "each parameter is passed once, either by position or by name",
+        quote! {()},
+    );
+    // names are unraw, such as `type`
+    let param = syn::parse_str::<syn::Ident>(name)
+        .unwrap_or_else(|_| syn::Ident::new_raw(name, Span::call_site()));
+
+    quote! {{
+        #[allow(non_camel_case_types)]
+        struct #param;
+        trait __Name {
+            type Name;
+        }
+        impl<V> __Name for V {
+            type Name = #param;
+        }
+        #passed_once
+        fn duplicate<V: __Name>(_: V) where V::Name: PassedOnce {}
+
+        if false {
+            duplicate($#val)
+        }
+        #any
+    }}
+}
+
+/// Report the unknown parameter `$__name` at the span of its value `$__val`,
+/// suggesting the closest of `names` by edit distance.
+///
+/// The argument is only known where the macro is invoked,
+/// so the closest name is selected by a constant evaluated there.
+/// Each selection has a function bound by a [diagnostic_trait],
+/// which names the argument by a unit struct, as in [duplicate_parameter].
+fn unknown_parameter(names: &[String], expected: &str) -> pm2::TokenStream {
+    let any = any_value();
+    let count = names.len();
+    let row = names.iter().map(String::len).max().unwrap_or_default() + 1;
+    // braces of the signature are format arguments of the diagnostic
+    let expected = expected.replace('{', "{{").replace('}', "}}");
+    let note = match names.is_empty() {
+        true => "no parameters can be passed by name".to_string(),
+        false => format!(
+            "parameters passed by name: {}",
+            names
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    let checks = names
+        .iter()
+        .map(Some)
+        .chain([None])
+        .enumerate()
+        .map(|(idx, name)| {
+            let message = match name {
+                Some(name) => format!(
+                    "unknown parameter `{{Self}}`, did you mean `{}`?\nexpected: {}",
+                    name, expected
+                ),
+                None => format!("unknown parameter `{{Self}}`\nexpected: {}", expected),
+            };
+            let unknown = syn::Ident::new(&format!("Parameter{}", idx), Span::call_site());
+            let unknown_trait = diagnostic_trait(
+                &unknown,
+                &message,
+                "unknown parameter",
+                &note,
+                quote! {__Closest<#idx>},
+            );
+
+            quote! {
+                #unknown_trait
+                impl __Closest<#idx> {
+                    fn check<V: __Name>(_: V) where V::Name: #unknown {}
+                }
+            }
+        });
+
+    quote! {{
+        #[allow(non_camel_case_types)]
+        struct $__name;
+        struct __Closest<const N: usize>;
+        trait __Name {
+            type Name;
+        }
+        impl<V> __Name for V {
+            type Name = $__name;
+        }
+        #(#checks)*
 
-            match output {
-                MacroType::Function | MacroType::StructTuple => quote! {
-                    (#macro_signature) => {
-                        #func_path_root #item_ident(#func_signature)
+        // levenshtein distance between an argument and a parameter name
+        const fn distance(arg: &[u8], name: &[u8]) -> usize {
+            let mut row = [0usize; #row];
+            let mut j = 0;
+            while j <= name.len() {
+                row[j] = j;
+                j += 1;
+            }
+
+            let mut i = 0;
+            while i < arg.len() {
+                let mut diagonal = row[0];
+                row[0] = i + 1;
+                let mut j = 0;
+                while j < name.len() {
+                    let above = row[j + 1];
+                    let mut min = diagonal + (arg[i] != name[j]) as usize;
+                    if above + 1 < min {
+                        min = above + 1;
                     }
-                },
-                MacroType::Struct => quote! {
-                    (#macro_signature) => {
-                        #func_path_root #item_ident{#func_signature}
+                    if row[j] + 1 < min {
+                        min = row[j] + 1;
                     }
-                },
+                    row[j + 1] = min;
+                    diagonal = above;
+                    j += 1;
+                }
+                i += 1;
+            }
+
+            row[name.len()]
+        }
+
+        // names differing in more than half of their characters are not suggested
+        const fn closest(arg: &str) -> usize {
+            const NAMES: [&str; #count] = [#(#names),*];
+
+            let arg = match arg.as_bytes() {
+                [b'r', b'#', arg @ ..] => arg,
+                arg => arg,
+            };
+            let mut closest = #count;
+            let mut min = usize::MAX;
+            let mut idx = 0;
+            while idx < #count {
+                let name = NAMES[idx].as_bytes();
+                let dist = distance(arg, name);
+                let longest = match arg.len() > name.len() {
+                    true => arg.len(),
+                    false => name.len(),
+                };
+                if dist < min && dist * 2 <= longest {
+                    closest = idx;
+                    min = dist;
+                }
+                idx += 1;
             }
 
-            // quote! {
-            //     (#macro_signature) => {
-            //         #func_path_root #item_ident(#func_signature)
-            //     }
-            // }
+            closest
+        }
+
+        if false {
+            __Closest::<{ closest(stringify!($__name)) }>::check($__val)
+        }
+        #any
+    }}
+}
+
+/// Trait `ident` with a custom diagnostic, reported where a bound on it is not satisfied.
+///
+/// The trait is only implemented for `implementor`, with `#[diagnostic::do_not_recommend]`,
+/// so that the diagnostic neither suggests implementing it nor lists the types implementing it.
+fn diagnostic_trait(
+    ident: &syn::Ident,
+    message: &str,
+    label: &str,
+    note: &str,
+    implementor: pm2::TokenStream,
+) -> pm2::TokenStream {
+    quote! {
+        #[diagnostic::on_unimplemented(message = #message, label = #label, note = #note)]
+        trait #ident {}
+        #[diagnostic::do_not_recommend]
+        impl #ident for #implementor {}
+    }
+}
+
+/// Value of any type, which ends the expansion of a diagnostic.
+///
+/// An invalid call in a typed position, such as `let a: i32 = function!(...)`,
+/// then reports no mismatched types besides the diagnostic.
+/// Unlike `loop {}`, it does not diverge, so following statements are not reported as unreachable.
+fn any_value() -> pm2::TokenStream {
+    quote! {::core::option::Option::None.unwrap()}
+}
+
+/// Expand a permutation into one permutation for each combination of names of its named items.
+fn expand_aliases<P: ToMacroSlot + Clone>(
+    permutation: Vec<PermutedItem<P>>,
+) -> Vec<Vec<PermutedItem<P>>> {
+    permutation
+        .into_iter()
+        .fold(vec![vec![]], |expanded, item| {
+            let names = match &item {
+                PermutedItem::Named(p) => std::iter::once(item.clone())
+                    .chain(p.slot_aliases().into_iter().map(PermutedItem::Named))
+                    .collect(),
+                PermutedItem::Positional(_) | PermutedItem::Default(_) => vec![item],
+            };
+
+            expanded
+                .into_iter()
+                .flat_map(|seq| {
+                    names
+                        .iter()
+                        .map(move |name| [seq.as_slice(), std::slice::from_ref(name)].concat())
+                })
+                .collect()
         })
-        .collect();
+}
 
-    let _macro_mod = syn::Ident::new(
-        &format!("{}_macros", item_ident.to_token_stream()),
+/// Emit deprecation warnings before an expression, if any.
+fn with_warnings(
+    warnings: impl IntoIterator<Item = pm2::TokenStream>,
+    expr: pm2::TokenStream,
+) -> pm2::TokenStream {
+    let warnings = warnings.into_iter().collect::<Vec<_>>();
+
+    match warnings.is_empty() {
+        true => expr,
+        false => quote! {{ #(#warnings)* #expr }},
+    }
+}
+
+/// Path prefix used to call the item from inside the macro.
+fn item_path_root(item_path: Option<&syn::Path>) -> pm2::TokenStream {
+    item_path
+        .map(|g| {
+            let segments = module_segments(g);
+            quote! {$crate :: #(#segments ::)*}
+        })
+        .unwrap_or_default()
+}
+
+/// Returns `true` if the module path starts with `self` or `super`.
+pub fn is_relative_path(path: &syn::Path) -> bool {
+    path.leading_colon.is_none()
+        && path
+            .segments
+            .first()
+            .is_some_and(|seg| seg.ident == "self" || seg.ident == "super")
+}
+
+/// Segments of a module path relative to the crate root, without a leading `crate`.
+fn module_segments(path: &syn::Path) -> impl Iterator<Item = &syn::PathSegment> {
+    path.segments
+        .iter()
+        .enumerate()
+        .filter(|(idx, seg)| !(*idx == 0 && seg.ident == crate::ROOT_VISIBILITY_IDENT))
+        .map(|(_, seg)| seg)
+}
+
+/// Name of the hidden macro definition.
+///
+/// Exported macros share a single namespace in the crate root,
+/// so each module segment is prefixed with its length to keep names unique:
+/// `net::http::client::get` becomes `__3net_4http_6client_get__`.
+fn macro_dunder_ident(item_path: Option<&syn::Path>, item_ident: &syn::Ident) -> syn::Ident {
+    let module = item_path
+        .map(|p| {
+            // items in the crate root keep `crate` to stay distinct from items without a path
+            let segments = match p.is_ident(crate::ROOT_VISIBILITY_IDENT) {
+                true => p.segments.iter().collect::<Vec<_>>(),
+                false => module_segments(p).collect(),
+            };
+
+            segments
+                .into_iter()
+                .map(|seg| {
+                    let seg = seg.ident.unraw().to_string();
+                    format!("{}{}_", seg.len(), seg)
+                })
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+
+    syn::Ident::new(
+        &format!("__{}{}__", module, item_ident.unraw()),
         Span::call_site(),
+    )
+}
+
+/// Generate a hidden constant describing the parameters of the item in declaration order,
+/// as `(name, type, position, default value)` tuples.
+/// The receiver of a method is included as `self`, and tuple fields are named by their index.
+///
+/// ```ignore
+/// #[doc(hidden)]
+/// #[allow(non_upper_case_globals)]
+/// pub const __DEFAMED_SIG_function: &[(&str, &str, usize, Option<&str>)] = &[
+///     ("a", "u8", 0usize, None),
+///     ("b", "u8", 1usize, Some("a * 2")),
+/// ];
+/// ```
+pub fn generate_signature_const<P: ToDocInfo>(item: &MacroItem, params: &[P]) -> pm2::TokenStream {
+    let tuple = matches!(
+        item.output,
+        MacroType::StructTuple | MacroType::EnumVariantTuple
+    );
+    let vis = &item.vis;
+    let const_ident = syn::Ident::new(
+        &format!("__DEFAMED_SIG_{}", item.ident.unraw()),
+        item.ident.span(),
     );
 
-    let macro_def_attr = match &vis {
+    let entries = item
+        .receiver
+        .iter()
+        .cloned()
+        .chain(params.iter().map(|p| p.to_doc_info()))
+        .chain(item.rest.iter().map(|rest| rest.doc.clone()))
+        .enumerate()
+        .map(|(position, doc)| {
+            let name = match tuple {
+                true => position.to_string(),
+                false => doc.ident,
+            };
+            let ty = doc.ty;
+            let default_value = match doc.default_value {
+                Some(val) => quote! {Some(#val)},
+                None => quote! {None},
+            };
+
+            quote! {(#name, #ty, #position, #default_value)}
+        });
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        #vis const #const_ident: &[(&str, &str, usize, Option<&str>)] = &[#(#entries),*];
+    }
+}
+
+/// Wrap the macro match arms in a hidden `macro_rules!` definition,
+/// then re-export the macro under the same name and visibility as the item.
+fn wrap_macro<P: ToDocInfo>(
+    item: &MacroItem,
+    params: &[P],
+    macro_matches: pm2::TokenStream,
+) -> pm2::TokenStream {
+    let MacroItem {
+        vis,
+        path: item_path,
+        ident: item_ident,
+        ..
+    } = item;
+
+    let macro_def_attr = match vis {
         Visibility::Public(_) => quote! {#[macro_export]},
         Visibility::Restricted(_) | Visibility::Inherited => quote! {},
     };
 
-    let func_dunder_ident = syn::Ident::new(
-        &format!(
-            "__{}{}__",
-            match &item_path {
-                Some(p) => format!("{}_", p.to_token_stream()),
-                None => "".to_string(),
-            },
-            item_ident.to_token_stream()
-        ),
-        Span::call_site(),
-    );
+    let func_dunder_ident = macro_dunder_ident(item_path.as_ref(), item_ident);
 
     // let full_func_path = match func_path {
     //     Some(p) => quote! {crate::#p::#func_ident},
     //     None => quote! {crate::#func_ident},
     // };
-    let item_prefix = output.to_string();
+    let item_link = format!(
+        "{}{}",
+        item.output,
+        item.item.to_token_stream().to_string().replace(' ', "")
+    );
 
-    let doc_type_info = first_ref
+    let doc_type_info = item
+        .receiver
         .iter()
+        .cloned()
+        .chain(params.iter().map(|p| p.to_doc_info()))
+        .chain(item.rest.iter().map(|rest| DocInfo {
+            ident: format!("{}...", rest.doc.ident),
+            ..rest.doc.clone()
+        }))
         .map(|p| {
-            let info = p.to_doc_info().to_string();
+            let info = p.to_string();
             quote! {#[doc = concat!("- ", #info)]}
         })
         .collect::<pm2::TokenStream>();
@@ -141,7 +1821,7 @@ pub fn generate_func_macro<P: ToMacroPattern + ToDocInfo + Clone + PartialEq + D
             );
 
             #[doc(inline)]
-            #[doc = concat!("[`defamed`] wrapper for [`", #item_prefix, stringify!(#item_ident), "`]")]
+            #[doc = concat!("[`defamed`] wrapper for [`", #item_link, "`]")]
             #[doc = ""]
             #doc_type_info
             #vis use #func_dunder_ident as #item_ident;
@@ -177,48 +1857,344 @@ fn create_macro_signature<P: ToMacroPattern>(params: &[P]) -> pm2::TokenStream {
     seq.to_token_stream()
 }
 
+/// Match pattern of a parameter passed by position or by name.
+fn macro_pattern<P>(item: PermutedItem<P>) -> Result<pm2::TokenStream, syn::Error>
+where
+    P: Clone,
+    PermutedItem<P>: ToMacroPattern,
+{
+    item.to_macro_pattern().ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            "Parameter passed by position or name has no macro pattern",
+        )
+    })
+}
+
+/// Uses the reference pattern to list the value of each parameter in declaration order.
+///
+/// Unlike [create_func_call_signature], values are not yet placed inside the function call or struct literal.
+fn create_func_call_values<P>(
+    reference: &[PermutedItem<P>],
+    params: &[PermutedItem<P>],
+) -> Result<Vec<pm2::TokenStream>, syn::Error>
+where
+    P: ToMacroSlot + Clone + PartialEq,
+{
+    collect_results(
+        reference
+            .iter()
+            .map(|r| match params.iter().find(|item| *item == r) {
+                Some(PermutedItem::Positional(p) | PermutedItem::Named(p)) => {
+                    let val = p.slot_metavar();
+                    Ok(p.slot_wrap(quote! {$#val}))
+                }
+                Some(PermutedItem::Default(p)) => p.slot_default().ok_or_else(|| {
+                    syn::Error::new(
+                        Span::call_site(),
+                        "Parameter without a default value is omitted from a permutation",
+                    )
+                }),
+                None => Err(syn::Error::new(
+                    Span::call_site(),
+                    "Parameter of the function is missing from a permutation",
+                )),
+            }),
+    )
+}
+
 /// Uses the reference pattern to order the parameters in the function call.
 ///
 /// All elements in `reference` must have an equal (by [PartialEq]) in `params`.
 ///
 /// If there are more elements in `params` than in `reference`, the extra elements are appended to the end.
-fn create_func_call_signature<P>(reference: &[P], params: &[P]) -> pm2::TokenStream
+fn create_func_call_signature<P>(
+    reference: &[P],
+    params: &[P],
+) -> Result<pm2::TokenStream, syn::Error>
 where
-    P: ToMacroPattern + PartialEq + Debug,
+    P: ToMacroPattern + PartialEq,
 {
-    assert!(
-        reference.len() <= params.len(),
-        "ref: {:?}\nparams: {:?}",
-        reference,
+    if reference.len() > params.len() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "Permutation has fewer parameters than the function",
+        ));
+    }
+
+    let mut seq = collect_results(reference.iter().map(|r| {
         params
-    );
+            .iter()
+            .find(|item| *item == r)
+            .map(|p| p.to_func_call_pattern())
+            .ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    "Parameter of the function is missing from a permutation",
+                )
+            })
+    }))?
+    .into_iter()
+    .collect::<Punctuated<pm2::TokenStream, Comma>>();
 
-    let mut seq: Punctuated<pm2::TokenStream, Comma> = reference
+    let additional = params[reference.len()..]
         .iter()
-        .map(|r| {
-            let p = params
-                .iter()
-                .find(|item| *item == r)
-                .expect("parameter must exist");
+        .map(|p| p.to_func_call_pattern());
+    seq.extend(additional);
 
-            p.to_func_call_pattern()
-        })
-        .collect();
+    Ok(seq.to_token_stream())
+}
 
-    match params.len().cmp(&reference.len()) {
-        std::cmp::Ordering::Less => {
-            unimplemented!("reference must have at least as many elements as params")
-        }
-        std::cmp::Ordering::Equal => (),
-        std::cmp::Ordering::Greater => {
-            // todo!();
-            let additional = params[reference.len()..]
-                .iter()
-                .map(|p| p.to_func_call_pattern());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_macro_dunder_ident() {
+        let ident = |path: Option<pm2::TokenStream>, item: pm2::TokenStream| {
+            let path = path.map(|p| syn::parse2::<syn::Path>(p).unwrap());
+            let item = syn::parse2::<syn::Ident>(item).unwrap();
+
+            macro_dunder_ident(path.as_ref(), &item).to_string()
+        };
+
+        assert_eq!(ident(None, quote! {func}), "__func__");
+        assert_eq!(
+            ident(Some(quote! {crate}), quote! {func}),
+            "__5crate_func__"
+        );
+        assert_eq!(
+            ident(Some(quote! {net::http::client}), quote! {get}),
+            "__3net_4http_6client_get__"
+        );
+        assert_eq!(
+            ident(Some(quote! {crate::net::http::client}), quote! {get}),
+            "__3net_4http_6client_get__"
+        );
+        assert_eq!(ident(Some(quote! {r#type}), quote! {r#fn}), "__4type_fn__");
+
+        // segments containing underscores do not collide
+        assert_ne!(
+            ident(Some(quote! {a_b::c}), quote! {func}),
+            ident(Some(quote! {a::b_c}), quote! {func})
+        );
+    }
+
+    #[test]
+    fn test_item_path_root() {
+        let root = |path: pm2::TokenStream| {
+            let path = syn::parse2::<syn::Path>(path).unwrap();
+            item_path_root(Some(&path)).to_string()
+        };
+
+        assert_eq!(root(quote! {crate}), quote! {$crate ::}.to_string());
+        assert_eq!(
+            root(quote! {a::b::c}),
+            quote! {$crate :: a :: b :: c ::}.to_string()
+        );
+        assert_eq!(
+            root(quote! {crate::a::b}),
+            quote! {$crate :: a :: b ::}.to_string()
+        );
+        assert!(item_path_root(None).is_empty());
+    }
+
+    #[test]
+    fn test_bound_params() {
+        let bound = |item_fn: pm2::TokenStream| {
+            let item_fn: syn::ItemFn = syn::parse2(item_fn).unwrap();
+            let params =
+                crate::permute::params::FunctionParams::from_punctuated(item_fn.sig.inputs)
+                    .unwrap();
+            bound_params(&params.params)
+        };
 
-            seq.extend(additional);
+        assert_eq!(bound(quote! {fn f(a: u8, #[def(1)] b: u8) {}}), 0);
+        assert_eq!(bound(quote! {fn f(a: u8, #[def(a)] b: u8) {}}), 1);
+        assert_eq!(
+            bound(quote! {fn f(a: u8, b: u8, #[def(a)] c: u8, d: u8) {}}),
+            1
+        );
+        assert_eq!(
+            bound(quote! {fn f(a: u8, b: u8, #[def(a)] c: u8, #[def(c + 1)] d: u8) {}}),
+            3
+        );
+        assert_eq!(
+            bound(quote! {fn f(a: u8, #[def(Some(a))] b: Option<u8>) {}}),
+            1
+        );
+    }
+
+    #[test]
+    fn test_func_call_values() {
+        let item_fn: syn::ItemFn = syn::parse2(quote! {fn f(a: u8, #[def(1)] b: u8) {}}).unwrap();
+        let params = crate::permute::params::FunctionParams::from_punctuated(item_fn.sig.inputs)
+            .unwrap()
+            .params;
+        let (a, b) = (params[0].clone(), params[1].clone());
+        let reference = [
+            PermutedItem::Positional(a.clone()),
+            PermutedItem::Default(b.clone()),
+        ];
+
+        let values = create_func_call_values(
+            &reference,
+            &[
+                PermutedItem::Named(b.clone()),
+                PermutedItem::Positional(a.clone()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(values.len(), 2);
+
+        // required parameters are never omitted, and every parameter is part of a permutation
+        assert!(create_func_call_values(
+            &reference,
+            &[
+                PermutedItem::Default(a.clone()),
+                PermutedItem::Default(b.clone())
+            ],
+        )
+        .is_err());
+        assert!(
+            create_func_call_values(&reference, &[PermutedItem::Positional(a.clone())]).is_err()
+        );
+
+        // omitted parameters are not matched by the macro
+        assert!(macro_pattern(PermutedItem::Named(b.clone())).is_ok());
+        assert!(macro_pattern(PermutedItem::Positional(a)).is_ok());
+        assert!(macro_pattern(PermutedItem::Default(b)).is_err());
+    }
+
+    #[test]
+    fn test_default_checks() {
+        let checks = |item_fn: pm2::TokenStream| {
+            let item_fn: syn::ItemFn = syn::parse2(item_fn).unwrap();
+            let params =
+                crate::permute::params::FunctionParams::from_punctuated(item_fn.sig.inputs)
+                    .unwrap();
+            generate_default_checks(&item_fn.sig.generics, &params.params).to_string()
+        };
+
+        assert!(checks(quote! {fn f(a: u8, #[opt] b: Option<u8>) {}}).is_empty());
+        assert!(checks(quote! {fn f<T>(a: T, #[def] b: Vec<T>) {}}).is_empty());
+        assert!(checks(quote! {fn f<const N: usize>(#[def] a: [u8; N]) {}}).is_empty());
+        assert!(checks(quote! {fn f(#[def(1)] a: impl Into<u8>) {}}).is_empty());
+
+        let value = checks(quote! {fn f<'a, T>(a: &'a T, #[def(2)] b: u8) {}});
+        assert!(value.contains(&quote! {fn __defamed_defaults<'a, T>(a: &'a T, b: u8)}.to_string()));
+        assert!(value.contains(&quote! {let _: u8 = 2;}.to_string()));
+
+        let default = checks(quote! {fn f(#[def] a: String) {}});
+        assert!(default.contains(&quote! {default_value::<String>();}.to_string()));
+
+        // anything referring to `Self` is left out
+        assert!(checks(quote! {fn f(#[def(Self::ZERO)] a: u8) {}}).is_empty());
+        assert!(checks(quote! {fn f(a: Self, #[def(a.0)] b: u8) {}}).is_empty());
+        assert!(checks(quote! {fn f<T: From<Self>>(a: T, #[def(T::ONE)] b: u8) {}}).is_empty());
+        assert!(
+            checks(quote! {fn f<T>(a: T, #[def(T::ONE)] b: u8) where T: Into<Self> {}}).is_empty()
+        );
+
+        let method = checks(
+            quote! {fn f<T: Into<Self>>(a: u8, t: T, #[def(a + 1)] b: u8) where Self: Sized {}},
+        );
+        assert!(method.contains(&quote! {fn __defamed_defaults(a: u8, b: u8)}.to_string()));
+        assert!(!method.contains("where"));
+    }
+
+    impl ToDocInfo for DocInfo {
+        fn to_doc_info(&self) -> DocInfo {
+            self.clone()
         }
     }
 
-    seq.to_token_stream()
+    #[test]
+    fn test_signature() {
+        let doc = |ident: &str, ty: &str, default_value: Option<&str>| DocInfo {
+            ident: ident.to_string(),
+            ty: ty.to_string(),
+            default_value: default_value.map(str::to_string),
+        };
+        let item = |output: MacroType| {
+            MacroItem::new(
+                Visibility::Inherited,
+                None,
+                syn::Ident::new("item", Span::call_site()),
+                output,
+            )
+        };
+        let params = [doc("a", "i32", None), doc("b", "bool", Some("true"))];
+
+        assert_eq!(
+            signature(&item(MacroType::Function), &params),
+            "item!(a: i32, b: bool = true)"
+        );
+        assert_eq!(
+            signature(&item(MacroType::Struct), &params),
+            "item! { a: i32, b: bool = true, .. }"
+        );
+        assert_eq!(
+            signature(&item(MacroType::Struct), &params[..1]),
+            "item! { a: i32 }"
+        );
+        assert_eq!(
+            signature(&item(MacroType::StructTuple), &params),
+            "item!(i32, bool = true)"
+        );
+
+        let mut method = item(MacroType::Method);
+        method.receiver = Some(doc("self", "&mut Self", None));
+        method.rest = Some(RestParam {
+            doc: doc("rest", "Vec<u8>", None),
+            collected: quote! {},
+            empty: quote! {},
+        });
+        assert_eq!(
+            signature(&method, &params[..1]),
+            "item!(self: &mut Self, a: i32, rest: Vec<u8>...)"
+        );
+    }
+
+    #[test]
+    fn test_signature_const() {
+        let doc = |ident: &str, ty: &str, default_value: Option<&str>| DocInfo {
+            ident: ident.to_string(),
+            ty: ty.to_string(),
+            default_value: default_value.map(str::to_string),
+        };
+        let params = [doc("a", "i32", None), doc("b", "bool", Some("true"))];
+
+        let mut method = MacroItem::new(
+            Visibility::Inherited,
+            None,
+            syn::Ident::new("Type_method", Span::call_site()),
+            MacroType::Method,
+        );
+        method.receiver = Some(doc("self", "&Self", None));
+        assert_eq!(
+            generate_signature_const(&method, &params).to_string(),
+            quote! {
+                #[doc(hidden)]
+                #[allow(non_upper_case_globals)]
+                const __DEFAMED_SIG_Type_method: &[(&str, &str, usize, Option<&str>)] = &[
+                    ("self", "&Self", 0usize, None),
+                    ("a", "i32", 1usize, None),
+                    ("b", "bool", 2usize, Some("true"))
+                ];
+            }
+            .to_string()
+        );
+
+        // tuple fields are named by their index
+        let tuple = MacroItem::new(
+            Visibility::Inherited,
+            None,
+            syn::Ident::new("Tuple", Span::call_site()),
+            MacroType::StructTuple,
+        );
+        assert!(generate_signature_const(&tuple, &params)
+            .to_string()
+            .contains(&quote! {("1", "bool", 1usize, Some("true"))}.to_string()));
+    }
 }
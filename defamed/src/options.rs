@@ -0,0 +1,293 @@
+//! Arguments accepted by the `#[defamed(...)]` attribute.
+
+use proc_macro2::Span;
+use syn::{parse::ParseStream, punctuated::Punctuated, spanned::Spanned};
+
+/// Option selecting the macro generation strategy
+const STRATEGY_OPT: &str = "strategy";
+
+/// Flag making trailing `Option<T>` parameters optional
+const OPTION_FLAG: &str = "option";
+
+/// Flag generating a partial application macro alongside the macro of a function
+const PARTIAL_FLAG: &str = "partial";
+
+/// Flag wrapping calls of unsafe and foreign functions in an `unsafe` block
+const UNSAFE_FLAG: &str = "unsafe";
+
+/// Flag generating a constant describing the parameters of the item
+const SIGNATURE_FLAG: &str = "signature";
+
+/// All flags, passed as `flag` or `flag = bool`
+const FLAGS: [&str; 4] = [OPTION_FLAG, PARTIAL_FLAG, UNSAFE_FLAG, SIGNATURE_FLAG];
+
+/// Macro generation strategy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// One match arm for every accepted ordering of parameters.
+    Permute,
+    /// Recursive tt-muncher. Macro size grows linearly with the number of parameters.
+    Linear,
+}
+
+/// Parsed attribute arguments.
+///
+/// ```ignore
+/// #[defamed(path::to::module, strategy = linear, option, partial, unsafe, signature)]
+/// //        ^^^^^^^^^^^^^^^^ optional module path, must be the first argument
+/// //                          ^^^^^^^^^^^^^^^^^^^^^^^^^ options in any order
+/// ```
+///
+/// A flag in place of the module path is always the flag,
+/// so a module of the same name is passed as `crate::flag`.
+#[derive(Clone, Default)]
+pub struct MacroOptions {
+    /// Module path to the item, relative to the crate root
+    pub path: Option<syn::Path>,
+    /// Explicitly requested generation strategy
+    pub strategy: Option<Strategy>,
+    /// Trailing `Option<T>` parameters default to `None`
+    pub option: bool,
+    /// Generate a `{function}_partial!` macro that returns a closure over the parameters not passed
+    pub partial: bool,
+    /// Calls of unsafe functions are wrapped in an `unsafe` block by the macro
+    pub unsafe_call: bool,
+    /// Generate a hidden `__DEFAMED_SIG_{item}` constant describing the parameters
+    pub signature: bool,
+    /// Flags that were passed, including those passed as `false`
+    pub passed: Vec<&'static str>,
+}
+
+impl syn::parse::Parse for MacroOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut opts = Self::default();
+        let metas = Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated(input)?;
+
+        for (idx, meta) in metas.into_iter().enumerate() {
+            let flag = match &meta {
+                syn::Meta::Path(p) => FLAGS.into_iter().find(|f| p.is_ident(f)),
+                syn::Meta::NameValue(nv) => FLAGS.into_iter().find(|f| nv.path.is_ident(f)),
+                syn::Meta::List(_) => None,
+            };
+
+            match (meta, flag) {
+                (syn::Meta::NameValue(nv), None) if nv.path.is_ident(STRATEGY_OPT) => {
+                    if opts.strategy.is_some() {
+                        return Err(syn::Error::new(
+                            nv.span(),
+                            "strategy specified more than once",
+                        ));
+                    }
+                    opts.strategy = Some(Strategy::from_expr(&nv.value)?);
+                }
+                (syn::Meta::Path(p), Some(flag)) => opts.set_flag(flag, true, p.span())?,
+                (syn::Meta::NameValue(nv), Some(flag)) => {
+                    let value = match &nv.value {
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Bool(b),
+                            ..
+                        }) => b.value,
+                        other => {
+                            return Err(syn::Error::new(
+                                other.span(),
+                                format!("expected `{0} = true` or `{0} = false`", flag),
+                            ))
+                        }
+                    };
+                    opts.set_flag(flag, value, nv.span())?;
+                }
+                (syn::Meta::Path(p), None) if idx == 0 => opts.path = Some(p),
+                (syn::Meta::Path(p), None) => {
+                    return Err(syn::Error::new(
+                        p.span(),
+                        "module path must be the first argument",
+                    ))
+                }
+                (other, _) => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        format!(
+                            "unrecognized option. Expected a module path, `{}`, `{}`, `{}`, `{}` or `{} = ...`",
+                            OPTION_FLAG, PARTIAL_FLAG, UNSAFE_FLAG, SIGNATURE_FLAG, STRATEGY_OPT
+                        ),
+                    ))
+                }
+            }
+        }
+
+        Ok(opts)
+    }
+}
+
+impl MacroOptions {
+    /// Set a flag that may only be passed once.
+    fn set_flag(&mut self, flag: &'static str, value: bool, span: Span) -> syn::Result<()> {
+        if self.passed.contains(&flag) {
+            return Err(syn::Error::new(
+                span,
+                format!("{} specified more than once", flag),
+            ));
+        }
+        self.passed.push(flag);
+
+        match flag {
+            OPTION_FLAG => self.option = value,
+            PARTIAL_FLAG => self.partial = value,
+            UNSAFE_FLAG => self.unsafe_call = value,
+            _ => self.signature = value,
+        }
+        Ok(())
+    }
+
+    /// Options of an annotated function, taking options that were not passed from its parent item.
+    pub fn inherit(self, parent: &Self) -> Self {
+        let flag = |flag, value, inherited| match self.passed.contains(&flag) {
+            true => value,
+            false => inherited,
+        };
+
+        Self {
+            option: flag(OPTION_FLAG, self.option, parent.option),
+            partial: flag(PARTIAL_FLAG, self.partial, parent.partial),
+            unsafe_call: flag(UNSAFE_FLAG, self.unsafe_call, parent.unsafe_call),
+            signature: flag(SIGNATURE_FLAG, self.signature, parent.signature),
+            path: self.path.or_else(|| parent.path.clone()),
+            strategy: self.strategy.or(parent.strategy),
+            passed: self.passed,
+        }
+    }
+}
+
+impl Strategy {
+    /// Parse a strategy from the value of a name-value option.
+    fn from_expr(expr: &syn::Expr) -> syn::Result<Self> {
+        let ident = match expr {
+            syn::Expr::Path(syn::ExprPath { path, .. }) => path.get_ident(),
+            _ => None,
+        };
+
+        match ident.map(|i| i.to_string()).as_deref() {
+            Some("permute") => Ok(Self::Permute),
+            Some("linear") => Ok(Self::Linear),
+            _ => Err(syn::Error::new(
+                expr.span(),
+                "unknown strategy. Expected `permute` or `linear`",
+            )),
+        }
+    }
+
+    /// Pick a strategy from the number of arms the permutation strategy would generate.
+    pub fn select(requested: Option<Self>, permuted_arms: usize) -> Self {
+        match requested {
+            Some(s) => s,
+            None if permuted_arms > crate::MAX_PERMUTED_ARMS => Self::Linear,
+            None => Self::Permute,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quote::{quote, ToTokens};
+
+    #[test]
+    fn test_parse_options() {
+        let empty: MacroOptions = syn::parse2(quote! {}).unwrap();
+        assert!(empty.path.is_none());
+        assert!(empty.strategy.is_none());
+
+        let path_only: MacroOptions = syn::parse2(quote! {crate}).unwrap();
+        assert!(path_only.path.unwrap().is_ident("crate"));
+
+        let full: MacroOptions = syn::parse2(quote! {inner, strategy = linear}).unwrap();
+        assert!(full.path.unwrap().is_ident("inner"));
+        assert_eq!(full.strategy, Some(Strategy::Linear));
+
+        let strategy_only: MacroOptions = syn::parse2(quote! {strategy = permute}).unwrap();
+        assert!(strategy_only.path.is_none());
+        assert_eq!(strategy_only.strategy, Some(Strategy::Permute));
+        assert!(!strategy_only.option);
+
+        let option_only: MacroOptions = syn::parse2(quote! {option = true}).unwrap();
+        assert!(option_only.path.is_none());
+        assert!(option_only.option);
+
+        let option_last: MacroOptions = syn::parse2(quote! {inner, option}).unwrap();
+        assert!(option_last.path.unwrap().is_ident("inner"));
+        assert!(option_last.option);
+
+        let partial: MacroOptions =
+            syn::parse2(quote! {partial = true, strategy = linear}).unwrap();
+        assert!(partial.partial);
+        assert!(!partial.option);
+        assert!(!partial.unsafe_call);
+
+        let unsafe_call: MacroOptions = syn::parse2(quote! {crate, unsafe}).unwrap();
+        assert!(unsafe_call.path.unwrap().is_ident("crate"));
+        assert!(unsafe_call.unsafe_call);
+        assert!(!unsafe_call.signature);
+
+        let signature: MacroOptions = syn::parse2(quote! {signature = true, option}).unwrap();
+        assert!(signature.signature);
+        assert!(signature.option);
+
+        // `unsafe` is never a module path
+        let unsafe_only: MacroOptions = syn::parse2(quote! {unsafe}).unwrap();
+        assert!(unsafe_only.path.is_none());
+        assert!(unsafe_only.unsafe_call);
+
+        // flags in place of the module path are always flags
+        let flag_first: MacroOptions = syn::parse2(quote! {option, signature}).unwrap();
+        assert!(flag_first.path.is_none());
+        assert!(flag_first.option);
+        assert!(flag_first.signature);
+
+        let module: MacroOptions = syn::parse2(quote! {crate::option, option = false}).unwrap();
+        assert_eq!(module.path.to_token_stream().to_string(), "crate :: option");
+        assert!(!module.option);
+    }
+
+    #[test]
+    fn test_inherit_options() {
+        let parent: MacroOptions = syn::parse2(quote! {crate, partial, option}).unwrap();
+        let method: MacroOptions = syn::parse2(quote! {partial = false, signature}).unwrap();
+
+        let inherited = method.inherit(&parent);
+        assert!(inherited.path.unwrap().is_ident("crate"));
+        assert!(!inherited.partial);
+        assert!(inherited.option);
+        assert!(inherited.signature);
+    }
+
+    #[test]
+    fn test_parse_invalid_options() {
+        assert!(syn::parse2::<MacroOptions>(quote! {strategy = fast}).is_err());
+        assert!(syn::parse2::<MacroOptions>(quote! {strategy = linear, inner}).is_err());
+        assert!(
+            syn::parse2::<MacroOptions>(quote! {strategy = linear, strategy = permute}).is_err()
+        );
+        assert!(syn::parse2::<MacroOptions>(quote! {crate, option, option}).is_err());
+        assert!(syn::parse2::<MacroOptions>(quote! {crate, partial, partial = false}).is_err());
+        assert!(syn::parse2::<MacroOptions>(quote! {unsafe, unsafe}).is_err());
+        assert!(syn::parse2::<MacroOptions>(quote! {crate, signature, signature}).is_err());
+        assert!(syn::parse2::<MacroOptions>(quote! {crate, option = 1}).is_err());
+
+        // a flag in place of the module path is the flag, so the path comes too late
+        assert!(syn::parse2::<MacroOptions>(quote! {partial, crate}).is_err());
+    }
+
+    #[test]
+    fn test_select_strategy() {
+        assert_eq!(Strategy::select(None, 1), Strategy::Permute);
+        assert_eq!(
+            Strategy::select(None, crate::MAX_PERMUTED_ARMS + 1),
+            Strategy::Linear
+        );
+        assert_eq!(
+            Strategy::select(Some(Strategy::Permute), usize::MAX),
+            Strategy::Permute
+        );
+    }
+}
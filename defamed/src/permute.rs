@@ -3,20 +3,343 @@
 
 use std::fmt::Debug;
 
+use proc_macro2 as pm2;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{ext::IdentExt, parse::ParseStream, punctuated::Punctuated, spanned::Spanned};
+
 use crate::traits::ToDocInfo;
 
 pub mod fields;
 pub mod params;
 
-/// Data from the `#[def]` attribute
+/// Data from the `#[def]` or `#[opt]` attribute
 #[derive(Clone)]
 pub enum ParamAttr {
     /// No helper attribute
     None,
-    /// Use default trait for initialization
-    Default,
-    /// Use const expr for initialization
+    /// Use default trait for initialization, spanned at the attribute
+    Default(pm2::Span),
+    /// Use an expression for initialization
     Value(syn::Expr),
+    /// `Option<T>` initialized with `None`, supplied values are wrapped in `Some`
+    Optional,
+}
+
+/// Returns `true` if the attribute is a helper attribute of this crate.
+pub fn is_helper_attr(attr: &syn::Attribute) -> bool {
+    is_default_attr(attr)
+        || attr.path().is_ident(crate::REST_HELPER_ATTR)
+        || attr.path().is_ident(crate::KW_HELPER_ATTR)
+        || attr.path().is_ident(crate::POS_HELPER_ATTR)
+        || attr.path().is_ident(crate::ALIAS_HELPER_ATTR)
+        || attr.path().is_ident(crate::DEPRECATED_NAME_HELPER_ATTR)
+        || attr.path().is_ident(crate::NAME_HELPER_ATTR)
+}
+
+/// Returns `true` if a marker attribute without arguments, such as `#[kw]`, is present.
+pub fn has_marker_attr(attrs: &[syn::Attribute], marker: &str) -> Result<bool, syn::Error> {
+    match attrs.iter().find(|a| a.path().is_ident(marker)) {
+        Some(attr) => match &attr.meta {
+            syn::Meta::Path(_) => Ok(true),
+            _ => Err(syn::Error::new(
+                attr.span(),
+                format!("#[{}] does not accept arguments", marker),
+            )),
+        },
+        None => Ok(false),
+    }
+}
+
+/// Returns `true` if the attribute provides a default value.
+fn is_default_attr(attr: &syn::Attribute) -> bool {
+    attr.path().is_ident(crate::DEFAULT_HELPER_ATTR)
+        || attr.path().is_ident(crate::OPTIONAL_HELPER_ATTR)
+}
+
+/// Alternative name of a parameter or field, from `#[alias]` or `#[deprecated_name]`
+#[derive(Clone, Debug)]
+pub struct Alias {
+    pub ident: syn::Ident,
+    /// Passing an argument by this name emits a deprecation warning
+    pub deprecated: bool,
+}
+
+impl Alias {
+    /// Parse all aliases of a parameter or field.
+    ///
+    /// Each attribute accepts one or more comma-separated names.
+    pub fn from_attrs(attrs: &[syn::Attribute]) -> Result<Vec<Self>, syn::Error> {
+        let mut aliases = vec![];
+
+        for attr in attrs {
+            let deprecated = match attr.path() {
+                p if p.is_ident(crate::ALIAS_HELPER_ATTR) => false,
+                p if p.is_ident(crate::DEPRECATED_NAME_HELPER_ATTR) => true,
+                _ => continue,
+            };
+
+            let idents = match &attr.meta {
+                syn::Meta::List(l) => {
+                    l.parse_args_with(Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated)?
+                }
+                _ => Punctuated::new(),
+            };
+
+            if idents.is_empty() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "expected one or more names, such as #[alias(old_name)]",
+                ));
+            }
+
+            aliases.extend(idents.into_iter().map(|ident| Alias { ident, deprecated }));
+        }
+
+        Ok(aliases)
+    }
+
+    /// Warning emitted where an argument is passed by this name, if deprecated.
+    pub fn warning(&self, name: &str) -> Option<pm2::TokenStream> {
+        self.deprecated.then(|| {
+            proc_macro_warning::FormattedWarning::new_deprecated(
+                format!("__defamed_deprecated_{}", self.ident.unraw()),
+                format!(
+                    "the argument name `{}` is deprecated, use `{}` instead",
+                    self.ident.unraw(),
+                    name
+                ),
+                self.ident.span(),
+            )
+            .into_token_stream()
+        })
+    }
+}
+
+/// Error emitted in place of a default value that is not present.
+///
+/// Items without a default value always precede items with one and are never omitted,
+/// so this is only reached if the order of the items was not checked.
+pub fn missing_default(span: pm2::Span) -> pm2::TokenStream {
+    syn::Error::new(span, "missing default value").to_compile_error()
+}
+
+/// Combine all errors into a single error so that all of them are reported, if any.
+pub fn combine_errors(errors: impl IntoIterator<Item = syn::Error>) -> Result<(), syn::Error> {
+    match errors.into_iter().reduce(|mut e, other| {
+        e.combine(other);
+        e
+    }) {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Collect all results, combining every error into a single error so that all of them are reported.
+pub fn collect_results<T>(
+    results: impl IntoIterator<Item = Result<T, syn::Error>>,
+) -> Result<Vec<T>, syn::Error> {
+    let mut items = vec![];
+    let mut errors = vec![];
+
+    for res in results {
+        match res {
+            Ok(item) => items.push(item),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    combine_errors(errors).map(|_| items)
+}
+
+/// Returns an error for the first alias that is already the name or alias of an item.
+pub fn check_aliases<'a>(
+    items: impl Iterator<Item = (String, &'a [Alias])> + Clone,
+) -> Result<(), syn::Error> {
+    let mut names = items
+        .clone()
+        .map(|(name, _)| name)
+        .collect::<std::collections::HashSet<_>>();
+
+    for alias in items.flat_map(|(_, aliases)| aliases) {
+        if !names.insert(alias.ident.unraw().to_string()) {
+            return Err(syn::Error::new(
+                alias.ident.span(),
+                format!("`{}` is already used as a name", alias.ident.unraw()),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the type is written as `Option<T>`, with or without a path.
+pub fn is_option_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            path.segments.last().is_some_and(|seg| {
+                seg.ident == "Option"
+                    && matches!(&seg.arguments, syn::PathArguments::AngleBracketed(args) if args.args.len() == 1)
+            })
+        }
+        syn::Type::Group(group) => is_option_type(&group.elem),
+        syn::Type::Paren(paren) => is_option_type(&paren.elem),
+        _ => false,
+    }
+}
+
+/// Readable form of a type, independent of how its tokens are spaced when stringified:
+/// `& 'a Option < i32 >` becomes `&'a Option<i32>`.
+pub fn type_string(ty: &syn::Type) -> String {
+    [
+        ("& ", "&"),
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ::", "::"),
+        (":: ", "::"),
+        (" ,", ","),
+        (" ;", ";"),
+    ]
+    .into_iter()
+    .fold(ty.to_token_stream().to_string(), |s, (from, to)| {
+        s.replace(from, to)
+    })
+}
+
+/// Mark trailing `Option<T>` items without a helper attribute as optional.
+///
+/// Only trailing items are marked, as defaults cannot be followed by required items.
+pub fn infer_optional<'a>(
+    items: impl DoubleEndedIterator<Item = (&'a syn::Type, &'a mut ParamAttr)>,
+) {
+    for (ty, attr) in items.rev() {
+        match attr {
+            ParamAttr::None if is_option_type(ty) => *attr = ParamAttr::Optional,
+            ParamAttr::None => break,
+            ParamAttr::Default(_) | ParamAttr::Value(_) | ParamAttr::Optional => (),
+        }
+    }
+}
+
+impl ParamAttr {
+    /// Parse the helper attributes of a parameter or field of type `ty`.
+    pub fn from_attrs(attrs: &[syn::Attribute], ty: &syn::Type) -> Result<Self, syn::Error> {
+        let mut helpers = attrs.iter().filter(|a| is_default_attr(a));
+
+        let attr = match helpers.next() {
+            Some(a) => a,
+            None => return Ok(ParamAttr::None),
+        };
+
+        if let Some(other) = helpers.next() {
+            return Err(syn::Error::new(
+                other.span(),
+                format!(
+                    "only one of #[{}] or #[{}] can be used",
+                    crate::DEFAULT_HELPER_ATTR,
+                    crate::OPTIONAL_HELPER_ATTR
+                ),
+            ));
+        }
+
+        match attr.path().is_ident(crate::OPTIONAL_HELPER_ATTR) {
+            true => {
+                if !matches!(attr.meta, syn::Meta::Path(_)) {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        format!(
+                            "#[{}] does not accept arguments",
+                            crate::OPTIONAL_HELPER_ATTR
+                        ),
+                    ));
+                }
+
+                if !is_option_type(ty) {
+                    return Err(syn::Error::new(
+                        ty.span(),
+                        format!(
+                            "#[{}] can only be used on `Option<T>`",
+                            crate::OPTIONAL_HELPER_ATTR
+                        ),
+                    ));
+                }
+
+                Ok(ParamAttr::Optional)
+            }
+            false => Self::from_meta(&attr.meta),
+        }
+    }
+
+    /// Parse the meta of a `#[def]` attribute.
+    ///
+    /// The entire contents of a meta list is parsed as a single expression.
+    pub fn from_meta(meta: &syn::Meta) -> Result<Self, syn::Error> {
+        match meta {
+            syn::Meta::Path(path) => Ok(ParamAttr::Default(path.span())),
+            syn::Meta::List(l) => {
+                if l.tokens.is_empty() {
+                    return Err(syn::Error::new(
+                        l.span(),
+                        format!(
+                            "expected an expression. Use #[{}] to initialize with Default::default()",
+                            crate::DEFAULT_HELPER_ATTR
+                        ),
+                    ));
+                }
+
+                let expr = l.parse_args_with(|input: ParseStream| {
+                    let expr: syn::Expr = input.parse()?;
+
+                    match input.is_empty() {
+                        true => Ok(expr),
+                        false => Err(input.error(
+                            "unexpected tokens after default value. Expected a single expression",
+                        )),
+                    }
+                })?;
+
+                Ok(ParamAttr::Value(expr))
+            }
+            syn::Meta::NameValue(nv) => Err(syn::Error::new(
+                nv.span(),
+                format!(
+                    "name-values are not supported. Use #[{}] or #[{}(EXPRESSION)] instead.",
+                    crate::DEFAULT_HELPER_ATTR,
+                    crate::DEFAULT_HELPER_ATTR
+                ),
+            )),
+        }
+    }
+
+    /// Expression used in place of an omitted argument, if any.
+    pub fn default_expr(&self) -> Option<pm2::TokenStream> {
+        match self {
+            ParamAttr::None => None,
+            ParamAttr::Default(span) => {
+                Some(quote_spanned! {*span=> core::default::Default::default()})
+            }
+            ParamAttr::Value(v) => Some(v.to_token_stream()),
+            ParamAttr::Optional => Some(quote! {core::option::Option::None}),
+        }
+    }
+
+    /// Supplied argument, as passed to the item.
+    pub fn wrap(&self, value: pm2::TokenStream) -> pm2::TokenStream {
+        match self {
+            ParamAttr::Optional => quote! {core::option::Option::Some(#value)},
+            ParamAttr::None | ParamAttr::Default(_) | ParamAttr::Value(_) => value,
+        }
+    }
+
+    /// Default value shown in documentation, if any.
+    pub fn doc_value(&self) -> Option<String> {
+        match self {
+            ParamAttr::None => None,
+            ParamAttr::Default(_) => Some("Default::default()".to_string()),
+            ParamAttr::Value(expr) => Some(expr.to_token_stream().to_string()),
+            ParamAttr::Optional => Some("None".to_string()),
+        }
+    }
 }
 
 /// A single permuted item
@@ -54,41 +377,37 @@ impl<T: Clone + ToDocInfo> ToDocInfo for PermutedItem<T> {
 
 impl<T: Clone> PermutedItem<T> {
     /// Get the inner value
-    fn inner(&self) -> &T {
+    pub fn inner(&self) -> &T {
         match self {
             Self::Positional(i) => i,
             Self::Named(i) => i,
             Self::Default(i) => i,
         }
     }
-
-    /// Split the items into used and unused default values while maintaining order.
-    /// The items must only contain named and default variants.
-    fn parition_named_defaults(
-        items: &[PermutedItem<T>],
-    ) -> (Vec<PermutedItem<T>>, Vec<PermutedItem<T>>) {
-        let res: (Vec<_>, Vec<_>) = items.iter().cloned().partition(|def| match def {
-            PermutedItem::Named(_) => true,
-            PermutedItem::Default(_) => false,
-            _ => panic!("unexpected variant"),
-        });
-
-        res
-    }
 }
 
 /// Generate all permutations of positional items and default items.
 ///
+/// Of `required` and `default` concatenated, the first `pos_only` items are positional-only
+/// and are never permuted as [PermutedItem::Named].
+/// The last `kw_only` items are keyword-only and are never permuted as [PermutedItem::Positional].
+///
 /// Returns a matrix of tuples of positional and default permutations.
 ///
 /// The first permutation in the permutation matrix is guraranteed to contain
-/// only [PermutedItem::Named] elements in the original order (`required`, `default` concatenated).
+/// elements in the original order (`required`, `default` concatenated).
+/// Without positional-only items, all of them are [PermutedItem::Named].
 #[allow(clippy::type_complexity)]
 pub fn permute<T: Clone + Debug>(
     required: Vec<T>,
     default: Vec<T>,
+    pos_only: usize,
+    kw_only: usize,
 ) -> Vec<(Vec<PermutedItem<T>>, Vec<PermutedItem<T>>)> {
-    let named_permute = (0..=required.len())
+    let (pos_required, pos_default) = split_pos_only(required.len(), default.len(), pos_only);
+    let (kw_required, kw_default) = split_kw_only(required.len(), default.len(), kw_only);
+
+    let named_permute = (pos_required..=required.len() - kw_required)
         .flat_map(|idx| {
             let (positional, named) = required.split_at(idx);
 
@@ -106,19 +425,19 @@ pub fn permute<T: Clone + Debug>(
         })
         .collect::<Vec<_>>();
 
-    debug_assert!(if let Some(l) = named_permute.last() {
+    debug_assert!(if let (Some(l), 0) = (named_permute.last(), kw_required) {
         l.iter()
             .all(|item| matches!(item, PermutedItem::Positional(_)))
     } else {
         true
     });
 
-    let default_permute = permute_named_default(&default);
-    let default_positional_permute = permute_pos_default(&default);
+    let default_permute = permute_named_default(&default, pos_default);
 
-    // last element in named permutation matrix contains all positional parameters
-    let all_positional = match named_permute.last() {
-        Some(base) => {
+    // last element in named permutation matrix contains all positional parameters,
+    // unless a required parameter is keyword-only
+    let all_positional = match (named_permute.last(), kw_required) {
+        (Some(base), 0) => {
             // sanity check
             assert!(
                 base.iter()
@@ -126,12 +445,13 @@ pub fn permute<T: Clone + Debug>(
                 "all permuted items in the last permuted sequence must be positional."
             );
 
-            default_positional_permute
+            permute_pos_default(&default, default.len() - kw_default, pos_default)
                 .into_iter()
                 .map(|seq| (base.to_vec(), seq.to_vec()))
                 .collect::<Vec<_>>()
         }
-        None => default_positional_permute
+        (Some(_), _) => vec![],
+        (None, _) => permute_pos_default(&default, default.len() - kw_default, pos_default)
             .into_iter()
             .map(|seq| (vec![], seq))
             .collect(),
@@ -163,6 +483,80 @@ pub fn permute<T: Clone + Debug>(
     [named_pos, all_positional].concat()
 }
 
+/// Split the number of leading positional-only items into
+/// positional-only required and positional-only default items.
+fn split_pos_only(required: usize, default: usize, pos_only: usize) -> (usize, usize) {
+    let pos_required = pos_only.min(required);
+    let pos_default = (pos_only - pos_required).min(default);
+
+    (pos_required, pos_default)
+}
+
+/// Split the number of trailing keyword-only items into
+/// keyword-only required and keyword-only default items.
+fn split_kw_only(required: usize, default: usize, kw_only: usize) -> (usize, usize) {
+    let kw_default = kw_only.min(default);
+    let kw_required = (kw_only - kw_default).min(required);
+
+    (kw_required, kw_default)
+}
+
+/// Number of permutations [permute] generates for the given number of
+/// required, default, positional-only and keyword-only items, without generating them.
+pub fn permute_count(required: usize, default: usize, pos_only: usize, kw_only: usize) -> usize {
+    let (pos_required, pos_default) = split_pos_only(required, default, pos_only);
+    let (kw_required, kw_default) = split_kw_only(required, default, kw_only);
+
+    let named = (kw_required..=required - pos_required)
+        .map(factorial)
+        .fold(0, usize::saturating_add);
+    let named_default = permute_named_default_count(default, pos_default);
+    let positional_default = match kw_required {
+        0 => (1..=default - kw_default)
+            .map(|idx| match default - idx {
+                0 => 1,
+                rem => permute_named_default_count(rem, pos_default.saturating_sub(idx)),
+            })
+            .fold(0, usize::saturating_add),
+        _ => 0,
+    };
+
+    match named_default {
+        0 => named.saturating_add(positional_default),
+        _ => named
+            .saturating_mul(named_default)
+            .saturating_add(positional_default),
+    }
+}
+
+/// Number of permutations [permute_tuple_struct] generates for the given number of default items.
+pub fn permute_tuple_struct_count(default: usize) -> usize {
+    default + 1
+}
+
+/// Number of permutations [permute_named_default] generates for the given number of items.
+fn permute_named_default_count(defaults: usize, pos_only: usize) -> usize {
+    if defaults == 0 {
+        return 0;
+    }
+
+    // positional-only defaults are always unused
+    let named = defaults - pos_only;
+
+    // choose the used defaults, then permute them
+    (0..=named)
+        .map(|used| binomial(named, used).saturating_mul(factorial(used)))
+        .fold(0, usize::saturating_add)
+}
+
+fn factorial(n: usize) -> usize {
+    (1..=n).fold(1, usize::saturating_mul)
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k).fold(1, |acc, i| acc.saturating_mul(n - i) / (i + 1))
+}
+
 /// Special permutation case for tuple structs.
 ///
 /// Tuple structs elements are positional only.
@@ -222,32 +616,43 @@ fn permute_named<T: Clone>(named: &[T]) -> Vec<Vec<PermutedItem<T>>> {
 /// This function will not check for this.
 ///
 /// Additionally, default params can be used(named) or unused(default). These are also permuted as well.
-fn permute_named_default<T: Clone + Debug>(defaults: &[T]) -> Vec<Vec<PermutedItem<T>>> {
+///
+/// The first `pos_only` items cannot be named, so they are always unused.
+fn permute_named_default<T: Clone + Debug>(
+    defaults: &[T],
+    pos_only: usize,
+) -> Vec<Vec<PermutedItem<T>>> {
+    let (pos_only, defaults) = defaults.split_at(pos_only);
+    let pos_unused = pos_only
+        .iter()
+        .map(|item| PermutedItem::Default(item.to_owned()))
+        .collect::<Vec<_>>();
+
+    // used and unused defaults, in order
     let base_permute = (0..(1 << defaults.len()))
         .rev()
         .map(|num| {
-            let seq = defaults
+            let (used, unused): (Vec<_>, Vec<_>) = defaults
                 .iter()
                 .enumerate()
-                .map(|(pos, item)| {
-                    // if bit set, it is used
-                    if (num >> pos) & 1 != 0 {
-                        PermutedItem::Named(item.to_owned())
-                    } else {
-                        PermutedItem::Default(item.to_owned())
-                    }
-                })
-                .collect::<Vec<_>>();
+                // if bit set, it is used
+                .partition(|(pos, _)| (num >> pos) & 1 != 0);
 
-            seq
+            (
+                used.into_iter()
+                    .map(|(_, item)| PermutedItem::Named(item.to_owned()))
+                    .collect::<Vec<_>>(),
+                unused
+                    .into_iter()
+                    .map(|(_, item)| PermutedItem::Default(item.to_owned()))
+                    .collect::<Vec<_>>(),
+            )
         })
         .collect::<Vec<_>>();
 
     let res = base_permute
         .into_iter()
-        .flat_map(|seq| {
-            let (used, unused) = PermutedItem::<T>::parition_named_defaults(&seq);
-
+        .flat_map(|(used, unused)| {
             let mut used_permute = permute::permute(used);
 
             if !unused.is_empty() {
@@ -258,6 +663,7 @@ fn permute_named_default<T: Clone + Debug>(defaults: &[T]) -> Vec<Vec<PermutedIt
 
             used_permute.into_iter()
         })
+        .map(|seq| [pos_unused.as_slice(), seq.as_slice()].concat())
         .collect::<Vec<_>>();
 
     res.into_iter().filter(|item| !item.is_empty()).collect()
@@ -267,8 +673,14 @@ fn permute_named_default<T: Clone + Debug>(defaults: &[T]) -> Vec<Vec<PermutedIt
 ///
 /// This is for the special case where all preceding non-default parameters
 /// are used as positional parameters.
-fn permute_pos_default<T: Clone + Debug>(defaults: &[T]) -> Vec<Vec<PermutedItem<T>>> {
-    let res = (1..=defaults.len())
+/// Only the first `positional` defaults can be passed by position,
+/// and the first `pos_only` defaults cannot be named.
+fn permute_pos_default<T: Clone + Debug>(
+    defaults: &[T],
+    positional: usize,
+    pos_only: usize,
+) -> Vec<Vec<PermutedItem<T>>> {
+    let res = (1..=positional)
         .flat_map(|idx| {
             let (positional, named) = defaults.split_at(idx);
             let pos_params = positional
@@ -279,7 +691,7 @@ fn permute_pos_default<T: Clone + Debug>(defaults: &[T]) -> Vec<Vec<PermutedItem
             let inter = match named.len() {
                 0 => vec![pos_params],
                 _ => {
-                    let named_permute = permute_named_default(named);
+                    let named_permute = permute_named_default(named, pos_only.saturating_sub(idx));
 
                     named_permute
                         .into_iter()
@@ -317,6 +729,176 @@ mod tests {
             .collect::<String>()
     }
 
+    #[test]
+    fn test_param_attr_from_meta() {
+        fn parse(tokens: pm2::TokenStream) -> Result<ParamAttr, syn::Error> {
+            let attr: syn::Attribute = syn::parse_quote! {#tokens};
+            ParamAttr::from_meta(&attr.meta)
+        }
+
+        assert!(matches!(parse(quote! {#[def]}), Ok(ParamAttr::Default(_))));
+
+        for expr in [
+            quote! {42},
+            quote! {1 + 2},
+            quote! {Vec::new()},
+            quote! {&[]},
+            quote! {Point { x: 1, y: 2 }},
+            quote! {{ let a = 1; a * 2 }},
+        ] {
+            match parse(quote! {#[def(#expr)]}) {
+                Ok(ParamAttr::Value(v)) => {
+                    assert_eq!(v.to_token_stream().to_string(), expr.to_string())
+                }
+                _ => panic!("failed to parse default value: {}", expr),
+            }
+        }
+
+        assert!(parse(quote! {#[def()]}).is_err());
+        assert!(parse(quote! {#[def(1, 2)]}).is_err());
+        assert!(parse(quote! {#[def(1 2)]}).is_err());
+        assert!(parse(quote! {#[def = 1]}).is_err());
+    }
+
+    #[test]
+    fn test_param_attr_from_attrs() {
+        fn parse(param: pm2::TokenStream) -> Result<ParamAttr, syn::Error> {
+            let field: syn::FnArg = syn::parse2(param).unwrap();
+            match field {
+                syn::FnArg::Typed(t) => ParamAttr::from_attrs(&t.attrs, &t.ty),
+                syn::FnArg::Receiver(_) => unreachable!(),
+            }
+        }
+
+        assert!(matches!(parse(quote! {a: u8}), Ok(ParamAttr::None)));
+        assert!(matches!(
+            parse(quote! {#[def] a: u8}),
+            Ok(ParamAttr::Default(_))
+        ));
+        assert!(matches!(
+            parse(quote! {#[opt] a: Option<u8>}),
+            Ok(ParamAttr::Optional)
+        ));
+        assert!(matches!(
+            parse(quote! {#[opt] a: core::option::Option<u8>}),
+            Ok(ParamAttr::Optional)
+        ));
+
+        assert!(parse(quote! {#[opt] a: u8}).is_err());
+        assert!(parse(quote! {#[opt(1)] a: Option<u8>}).is_err());
+        assert!(parse(quote! {#[opt] #[def] a: Option<u8>}).is_err());
+    }
+
+    #[test]
+    fn test_alias_from_attrs() {
+        fn parse(param: pm2::TokenStream) -> Result<Vec<Alias>, syn::Error> {
+            let field: syn::FnArg = syn::parse2(param).unwrap();
+            match field {
+                syn::FnArg::Typed(t) => Alias::from_attrs(&t.attrs),
+                syn::FnArg::Receiver(_) => unreachable!(),
+            }
+        }
+
+        assert!(parse(quote! {a: u8}).unwrap().is_empty());
+
+        let aliases = parse(quote! {#[alias(b, c)] #[deprecated_name(d)] a: u8}).unwrap();
+        assert_eq!(
+            aliases
+                .iter()
+                .map(|a| (a.ident.to_string(), a.deprecated))
+                .collect::<Vec<_>>(),
+            [
+                ("b".to_string(), false),
+                ("c".to_string(), false),
+                ("d".to_string(), true)
+            ]
+        );
+        assert!(aliases[0].warning("a").is_none());
+        assert!(aliases[2].warning("a").is_some());
+
+        assert!(parse(quote! {#[alias] a: u8}).is_err());
+        assert!(parse(quote! {#[alias()] a: u8}).is_err());
+        assert!(parse(quote! {#[alias = b] a: u8}).is_err());
+        assert!(parse(quote! {#[alias(1)] a: u8}).is_err());
+    }
+
+    #[test]
+    fn test_check_aliases() {
+        let alias = |name: &str| Alias {
+            ident: syn::Ident::new(name, pm2::Span::call_site()),
+            deprecated: false,
+        };
+
+        let valid = [vec![alias("x")], vec![], vec![alias("y"), alias("z")]];
+        let names = ["a", "b", "c"];
+        assert!(check_aliases(
+            names
+                .iter()
+                .zip(&valid)
+                .map(|(n, a)| (n.to_string(), a.as_slice()))
+        )
+        .is_ok());
+
+        for invalid in [
+            [vec![alias("b")], vec![], vec![]],
+            [vec![alias("x")], vec![alias("x")], vec![]],
+            [vec![], vec![], vec![alias("y"), alias("y")]],
+        ] {
+            assert!(check_aliases(
+                names
+                    .iter()
+                    .zip(&invalid)
+                    .map(|(n, a)| (n.to_string(), a.as_slice()))
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_type_string() {
+        let ty = |tokens: pm2::TokenStream| type_string(&syn::parse2(tokens).unwrap());
+
+        assert_eq!(ty(quote! {&Self}), "&Self");
+        assert_eq!(ty(quote! {&'a mut [u8]}), "&'a mut [u8]");
+        assert_eq!(ty(quote! {Option<i32>}), "Option<i32>");
+        assert_eq!(
+            ty(quote! {std::collections::HashMap<&str, Vec<(u8, u8)>>}),
+            "std::collections::HashMap<&str, Vec<(u8, u8)>>"
+        );
+        assert_eq!(ty(quote! {[T; N]}), "[T; N]");
+        assert_eq!(ty(quote! {<T as Iterator>::Item}), "<T as Iterator>::Item");
+        assert_eq!(
+            ty(quote! {Box<dyn Send + 'static>}),
+            "Box<dyn Send + 'static>"
+        );
+    }
+
+    #[test]
+    fn test_infer_optional() {
+        let types: [syn::Type; 5] = [
+            syn::parse_quote! {Option<u8>},
+            syn::parse_quote! {u8},
+            syn::parse_quote! {Option<u8>},
+            syn::parse_quote! {u8},
+            syn::parse_quote! {Option<u8>},
+        ];
+        let mut attrs = [
+            ParamAttr::None,
+            ParamAttr::None,
+            ParamAttr::None,
+            ParamAttr::Default(pm2::Span::call_site()),
+            ParamAttr::None,
+        ];
+
+        infer_optional(types.iter().zip(attrs.iter_mut()));
+
+        assert!(matches!(attrs[0], ParamAttr::None));
+        assert!(matches!(attrs[1], ParamAttr::None));
+        assert!(matches!(attrs[2], ParamAttr::Optional));
+        assert!(matches!(attrs[3], ParamAttr::Default(_)));
+        assert!(matches!(attrs[4], ParamAttr::Optional));
+    }
+
     #[test]
     fn test_permute_tuple_struct() {
         fn assert_positional_default_split_at<T: Clone>(
@@ -343,6 +925,95 @@ mod tests {
         assert_positional_default_split_at(&permutations[2], 4);
     }
 
+    #[test]
+    fn test_permute_count() {
+        for required in 0..=4 {
+            for default in 0..=4 {
+                let items = (0..required + default).map(idx_to_str).collect::<Vec<_>>();
+                let (pos, def) = items.split_at(required);
+
+                for pos_only in 0..=required + default {
+                    for kw_only in 0..=required + default - pos_only {
+                        assert_eq!(
+                            permute_count(required, default, pos_only, kw_only),
+                            permute(pos.to_vec(), def.to_vec(), pos_only, kw_only).len(),
+                            "required: {}, default: {}, pos_only: {}, kw_only: {}",
+                            required,
+                            default,
+                            pos_only,
+                            kw_only
+                        );
+                    }
+                }
+                assert_eq!(
+                    permute_tuple_struct_count(default),
+                    permute_tuple_struct(pos.to_vec(), def.to_vec()).len()
+                );
+            }
+        }
+
+        // large counts saturate instead of overflowing
+        assert_eq!(permute_count(30, 30, 0, 0), usize::MAX);
+    }
+
+    #[test]
+    fn test_permute_kw_only() {
+        let items = (0..5).map(idx_to_str).collect::<Vec<_>>();
+
+        for required in 0..=items.len() {
+            let (pos, def) = items.split_at(required);
+
+            for kw_only in 0..=items.len() {
+                let keyword = &items[items.len() - kw_only..];
+                let permutations = permute(pos.to_vec(), def.to_vec(), 0, kw_only);
+
+                // keyword-only items are never positional
+                assert!(permutations
+                    .iter()
+                    .flat_map(|(p, d)| p.iter().chain(d))
+                    .all(|item| !(matches!(item, PermutedItem::Positional(_))
+                        && keyword.contains(item.inner()))));
+
+                // removing the keyword-only restriction only adds permutations
+                assert!(permutations.len() <= permute_count(pos.len(), def.len(), 0, 0));
+            }
+        }
+
+        // a keyword-only required item prevents positional defaults
+        let permutations = permute(vec!["a", "b"], vec!["c"], 0, 2);
+        assert!(permutations
+            .iter()
+            .all(|(_, d)| d.iter().all(|i| !matches!(i, PermutedItem::Positional(_)))));
+    }
+
+    #[test]
+    fn test_permute_pos_only() {
+        let items = (0..5).map(idx_to_str).collect::<Vec<_>>();
+
+        for required in 0..=items.len() {
+            let (pos, def) = items.split_at(required);
+
+            for pos_only in 0..=items.len() {
+                let positional = &items[..pos_only];
+                let permutations = permute(pos.to_vec(), def.to_vec(), pos_only, 0);
+
+                // positional-only items are never named
+                assert!(permutations
+                    .iter()
+                    .flat_map(|(p, d)| p.iter().chain(d))
+                    .all(|item| !(matches!(item, PermutedItem::Named(_))
+                        && positional.contains(item.inner()))));
+
+                // the first permutation keeps the original order
+                let (p, d) = permutations.first().unwrap();
+                assert_eq!(
+                    p.iter().chain(d).map(|i| i.inner()).collect::<Vec<_>>(),
+                    items.iter().collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
     /// Test inner named permute function
     #[test]
     fn test_permute_inner_named() {
@@ -366,7 +1037,7 @@ mod tests {
     fn test_permute_inner_named_defaults() {
         let mut items = vec!["a", "b"];
 
-        let permutations = permute_named_default(&items);
+        let permutations = permute_named_default(&items, 0);
 
         // 0 0
         // 0 1
@@ -385,7 +1056,7 @@ mod tests {
         );
 
         items.clear();
-        let permutations = permute_pos_default(&items);
+        let permutations = permute_pos_default(&items, items.len(), 0);
         assert!(permutations.is_empty());
     }
 
@@ -395,7 +1066,7 @@ mod tests {
 
         for i in 1..=9 {
             let inputs = &items[..i];
-            let permutations = permute_named_default(inputs);
+            let permutations = permute_named_default(inputs, 0);
             let first = permutations.first().unwrap();
 
             println!("9 defaults: {} branches", permutations.len());
@@ -418,7 +1089,7 @@ mod tests {
     fn test_permute_inner_positional_defaults() {
         let items = vec!["a", "b", "c"];
 
-        let permutations = permute_pos_default(&items);
+        let permutations = permute_pos_default(&items, items.len(), 0);
 
         // 0 0
         // 0 1
@@ -446,7 +1117,7 @@ mod tests {
     fn test_permute_positional_named() {
         let items = vec!["a", "b", "c", "d"];
 
-        let permutations = permute(items, vec![]);
+        let permutations = permute(items, vec![], 0, 0);
 
         let first_perm = permutations.first().unwrap();
 
@@ -485,7 +1156,7 @@ mod tests {
         let items = vec!["a", "b", "c", "d"];
         let defaults = vec!["e", "f"];
 
-        let permutations = permute(items, defaults);
+        let permutations = permute(items, defaults, 0, 0);
 
         let first_perm = permutations.first().unwrap();
 
@@ -529,7 +1200,7 @@ mod tests {
         for i in 1..=9 {
             let items = (0..i).map(idx_to_str).collect::<Vec<_>>();
 
-            let permutations = permute(items.clone(), vec![]);
+            let permutations = permute(items.clone(), vec![], 0, 0);
 
             println!("{} positionals: {} branches", i, permutations.len());
 
@@ -551,7 +1222,7 @@ mod tests {
     fn test_permute_9_default() {
         for i in 1..=9 {
             let items = (0..i).map(idx_to_str).collect::<Vec<_>>();
-            let permutations = permute(vec![], items.clone());
+            let permutations = permute(vec![], items.clone(), 0, 0);
 
             println!("{} defaults: {} branches", i, permutations.len());
 
@@ -577,7 +1248,7 @@ mod tests {
         for i in 0..=NUM {
             let (pos, def) = items.split_at(i);
 
-            let permutations = permute(pos.to_vec(), def.to_vec());
+            let permutations = permute(pos.to_vec(), def.to_vec(), 0, 0);
 
             println!(
                 "pos: {}, def: {}\tbranches: {}",
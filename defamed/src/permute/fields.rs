@@ -2,15 +2,18 @@ use std::fmt;
 
 use quote::quote;
 use quote::ToTokens;
+use syn::ext::IdentExt;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 
 use crate::traits::DocInfo;
 use crate::traits::StripAttributes;
+use crate::traits::ToDefaultCheck;
 use crate::traits::ToDocInfo;
 use crate::traits::ToMacroPattern;
+use crate::traits::ToMacroSlot;
 
-use super::{ParamAttr, PermutedItem};
+use super::{Alias, ParamAttr, PermutedItem};
 
 /// Parsed struct fields
 #[derive(Clone, Debug)]
@@ -31,6 +34,14 @@ pub struct StructField {
     pub is_tuple: bool,
     pub ty: syn::Type,
     pub default_value: ParamAttr,
+    /// Field can only be passed by name
+    pub kw_only: bool,
+    /// Field can only be passed by position
+    pub pos_only: bool,
+    /// Alternative names of the field
+    pub aliases: Vec<Alias>,
+    /// Alias used when passed by name, instead of the field name
+    named_as: Option<Alias>,
 
     /// Overrides all other fields for [ToMacroPattern],
     /// This represents the struct update syntax without a value (`..`).
@@ -91,23 +102,22 @@ impl ToMacroPattern for PermutedItem<StructField> {
         }
 
         match self {
-            Self::Positional(StructField { ident, .. }) => {
-                let pat = syn::Ident::new(&format!("{}_val", ident), ident.span());
+            Self::Positional(field) => {
+                let pat = field.metavar();
                 Some(quote! {$#pat: expr})
             }
 
-            Self::Named(StructField { ident, .. }) => {
-                // asd
-
-                let pat = syn::Ident::new(&format!("{}_val", ident), ident.span());
+            Self::Named(field) => {
+                let ident = match &field.named_as {
+                    Some(alias) => &alias.ident,
+                    None => &field.ident,
+                };
+                let pat = field.metavar();
 
                 Some(quote! {#ident: $#pat: expr})
             }
 
-            Self::Default(StructField { default_value, .. }) => match default_value {
-                ParamAttr::None => unimplemented!("default value must be present"),
-                ParamAttr::Default | ParamAttr::Value(_) => None,
-            },
+            Self::Default(_) => None,
         }
     }
 
@@ -117,33 +127,79 @@ impl ToMacroPattern for PermutedItem<StructField> {
         }
 
         match self {
-            PermutedItem::Positional(StructField {
-                ident, is_tuple, ..
-            }) => {
-                let pat = syn::Ident::new(&format!("{}_val", ident), ident.span());
-
-                match is_tuple {
-                    true => quote! {$#pat},
-                    false => quote! {#ident: $#pat},
-                }
+            PermutedItem::Positional(field) | PermutedItem::Named(field) => {
+                let pat = field.metavar();
+                field.slot_call(field.default_value.wrap(quote! {$#pat}))
             }
-            PermutedItem::Named(StructField { ident, .. }) => {
-                let pat = syn::Ident::new(&format!("{}_val", ident), ident.span());
+            PermutedItem::Default(field) => field.slot_call(
+                field
+                    .default_value
+                    .default_expr()
+                    .unwrap_or_else(|| super::missing_default(field.ty.span())),
+            ),
+        }
+    }
+}
 
-                quote! {#ident: $#pat}
-            }
-            PermutedItem::Default(StructField {
-                ident,
-                is_tuple,
-                default_value,
-                ..
-            }) => match (default_value, is_tuple) {
-                (ParamAttr::None, _) => unimplemented!("default value must be present"),
-                (ParamAttr::Default, true) => quote! {core::default::Default::default()},
-                (ParamAttr::Default, false) => quote! {#ident: core::default::Default::default()},
-                (ParamAttr::Value(expr), true) => quote! {#expr},
-                (ParamAttr::Value(expr), false) => quote! {#ident: #expr},
-            },
+impl ToMacroSlot for StructField {
+    fn slot_metavar(&self) -> syn::Ident {
+        self.metavar()
+    }
+
+    fn slot_named(&self) -> bool {
+        !self.is_tuple && !self.pos_only
+    }
+
+    fn slot_positional(&self) -> bool {
+        !self.kw_only
+    }
+
+    fn slot_aliases(&self) -> Vec<Self> {
+        self.aliases
+            .iter()
+            .map(|alias| Self {
+                named_as: Some(alias.clone()),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    fn slot_warning(&self) -> Option<proc_macro2::TokenStream> {
+        self.named_as
+            .as_ref()
+            .and_then(|alias| alias.warning(&self.ident.to_string()))
+    }
+
+    fn slot_default(&self) -> Option<proc_macro2::TokenStream> {
+        self.default_value.default_expr()
+    }
+
+    fn slot_call(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+
+        match self.is_tuple {
+            true => value,
+            false => quote! {#ident: #value},
+        }
+    }
+
+    fn slot_wrap(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        self.default_value.wrap(value)
+    }
+
+    fn slot_binding(&self) -> syn::Ident {
+        // tuple fields cannot be referred to by name
+        match self.is_tuple {
+            true => self.metavar(),
+            false => self.ident.clone(),
+        }
+    }
+
+    fn slot_bound_call(&self) -> proc_macro2::TokenStream {
+        // field init shorthand, the binding has the same name as the field
+        match self.is_tuple {
+            true => self.slot_binding().to_token_stream(),
+            false => self.ident.to_token_stream(),
         }
     }
 }
@@ -152,7 +208,7 @@ impl StripAttributes for StructFields {
     type Original = syn::Fields;
 
     fn strip_attributes(&self) -> Self::Original {
-        let x = self.fields.first().expect("at least one field expected");
+        let is_tuple = self.fields.first().is_some_and(|f| f.is_tuple);
 
         let fields = self
             .fields
@@ -161,7 +217,7 @@ impl StripAttributes for StructFields {
                 attrs: f
                     .attrs
                     .iter()
-                    .filter(|a| !a.path().is_ident(crate::DEFAULT_HELPER_ATTR))
+                    .filter(|a| !super::is_helper_attr(a))
                     .cloned()
                     .collect::<Vec<_>>(),
                 vis: f.vis.clone(),
@@ -176,7 +232,7 @@ impl StripAttributes for StructFields {
             })
             .collect();
 
-        match x.is_tuple {
+        match is_tuple {
             true => syn::Fields::Unnamed(syn::FieldsUnnamed {
                 paren_token: Default::default(),
                 unnamed: fields,
@@ -189,16 +245,33 @@ impl StripAttributes for StructFields {
     }
 }
 
+impl ToDefaultCheck for StructField {
+    fn check_param(&self) -> proc_macro2::TokenStream {
+        let binding = self.slot_binding();
+        let ty = &self.ty;
+
+        quote! {#binding: #ty}
+    }
+
+    fn check_name(&self) -> Option<String> {
+        (!self.is_tuple).then(|| self.ident.to_string())
+    }
+
+    fn check_type(&self) -> &syn::Type {
+        &self.ty
+    }
+
+    fn check_default(&self) -> &ParamAttr {
+        &self.default_value
+    }
+}
+
 impl ToDocInfo for StructField {
     fn to_doc_info(&self) -> DocInfo {
         DocInfo {
             ident: self.ident.to_string(),
-            ty: self.ty.to_token_stream().to_string(),
-            default_value: match &self.default_value {
-                ParamAttr::None => None,
-                ParamAttr::Default => Some("Default::default()".to_string()),
-                ParamAttr::Value(expr) => Some(expr.to_token_stream().to_string()),
-            },
+            ty: super::type_string(&self.ty),
+            default_value: self.default_value.doc_value(),
         }
     }
 }
@@ -209,10 +282,17 @@ impl StructFields {
         ident: syn::Ident,
         fields: Punctuated<syn::Field, syn::Token![,]>,
     ) -> Result<Self, syn::Error> {
-        let fields = fields
-            .into_iter()
-            .map(|f| StructField::from_field_type(f, None))
-            .collect::<Result<_, _>>()?;
+        let fields = super::collect_results(
+            fields
+                .into_iter()
+                .map(|f| StructField::from_field_type(f, None)),
+        )?;
+
+        super::check_aliases(
+            fields
+                .iter()
+                .map(|f| (f.ident.unraw().to_string(), f.aliases.as_slice())),
+        )?;
 
         Ok(Self { ident, fields })
     }
@@ -222,15 +302,25 @@ impl StructFields {
         ident: syn::Ident,
         fields: Punctuated<syn::Field, syn::Token![,]>,
     ) -> Result<Self, syn::Error> {
-        let fields = fields
-            .into_iter()
-            .enumerate()
-            .map(|(idx, field)| StructField::from_field_type(field, Some(idx)))
-            .collect::<Result<_, _>>()?;
+        let fields = super::collect_results(
+            fields
+                .into_iter()
+                .enumerate()
+                .map(|(idx, field)| StructField::from_field_type(field, Some(idx))),
+        )?;
 
         Ok(Self { ident, fields })
     }
 
+    /// Mark trailing `Option<T>` fields as optional.
+    pub fn infer_optional(&mut self) {
+        super::infer_optional(
+            self.fields
+                .iter_mut()
+                .map(|f| (&f.ty, &mut f.default_value)),
+        )
+    }
+
     /// Returns the first non-default item after the first default item, if any.
     pub fn first_invalid(&self) -> Option<&StructField> {
         let mut iter = self
@@ -240,6 +330,40 @@ impl StructFields {
 
         iter.find(|f| matches!(f.default_value, ParamAttr::None))
     }
+
+    /// Returns the first positional field after the first keyword-only field, if any.
+    pub fn first_positional_after_kw(&self) -> Option<&StructField> {
+        self.fields
+            .iter()
+            .skip_while(|f| !f.kw_only)
+            .find(|f| !f.kw_only)
+    }
+
+    /// Number of trailing keyword-only fields.
+    pub fn kw_only_count(&self) -> usize {
+        self.fields.iter().filter(|f| f.kw_only).count()
+    }
+
+    /// Returns the first positional-only field after the first field that can be named, if any.
+    pub fn first_pos_only_after_named(&self) -> Option<&StructField> {
+        self.fields
+            .iter()
+            .skip_while(|f| f.pos_only)
+            .find(|f| f.pos_only)
+    }
+
+    /// Number of leading positional-only fields.
+    pub fn pos_only_count(&self) -> usize {
+        self.fields.iter().filter(|f| f.pos_only).count()
+    }
+
+    /// Upper bound on the number of ways the fields of a single permutation can be named.
+    pub fn alias_variants(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|f| f.aliases.len() + 1)
+            .fold(1, usize::saturating_mul)
+    }
 }
 
 impl StructField {
@@ -248,42 +372,50 @@ impl StructField {
         field: syn::Field,
         tuple_elem: Option<usize>,
     ) -> Result<Self, syn::Error> {
-        // look for default attr
-        let mut default_value = ParamAttr::None;
-        if !field.attrs.is_empty() {
-            for attr in &field.attrs {
-                if attr.path().is_ident(crate::DEFAULT_HELPER_ATTR) {
-                    let meta = attr.meta.clone();
-
-                    match meta {
-                        syn::Meta::Path(_) => default_value = ParamAttr::Default,
-                        syn::Meta::List(l) => {
-                            let l_span = l.span();
-
-                            let first_item = l.tokens.into_iter().next().ok_or(syn::Error::new(
-                                l_span,
-                                "expected at least 1 item in metalist",
-                            ))?;
-
-                            let e: syn::Expr = syn::parse2(first_item.to_token_stream())?;
-                            default_value = ParamAttr::Value(e);
-                        }
-                        syn::Meta::NameValue(nv) => {
-                            let e = syn::Error::new(
-                                    nv.span(),
-                                    format!("name-values are not supported. Use #[{}] or #[{}(CONST_EXPRESSION)] instead.",
-                                        crate::DEFAULT_HELPER_ATTR,
-                                        crate::DEFAULT_HELPER_ATTR
-                                    ),
-                                );
-                            return Err(e);
-                        }
-                    }
-
-                    break;
-                }
-            }
-        };
+        if let Some(attr) = field.attrs.iter().find(|a| {
+            a.path().is_ident(crate::REST_HELPER_ATTR) || a.path().is_ident(crate::NAME_HELPER_ATTR)
+        }) {
+            return Err(syn::Error::new(
+                attr.span(),
+                format!(
+                    "#[{}] is only supported on function parameters",
+                    attr.path().to_token_stream()
+                ),
+            ));
+        }
+
+        let default_value = ParamAttr::from_attrs(&field.attrs, &field.ty)?;
+        let kw_only = super::has_marker_attr(&field.attrs, crate::KW_HELPER_ATTR)?;
+        let pos_only = super::has_marker_attr(&field.attrs, crate::POS_HELPER_ATTR)?;
+        let aliases = Alias::from_attrs(&field.attrs)?;
+
+        if let (Some(alias), true) = (aliases.first(), pos_only || tuple_elem.is_some()) {
+            return Err(syn::Error::new(
+                alias.ident.span(),
+                "positional-only fields cannot be passed by name",
+            ));
+        }
+
+        if kw_only && pos_only {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                format!(
+                    "only one of #[{}] or #[{}] can be used",
+                    crate::KW_HELPER_ATTR,
+                    crate::POS_HELPER_ATTR
+                ),
+            ));
+        }
+
+        if kw_only && tuple_elem.is_some() {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                format!(
+                    "#[{}] is not supported on tuple fields, as they cannot be named",
+                    crate::KW_HELPER_ATTR
+                ),
+            ));
+        }
 
         let res = match tuple_elem {
             Some(mut tup_id) => {
@@ -313,16 +445,26 @@ impl StructField {
                     is_tuple: true,
                     ty: field.ty,
                     default_value,
+                    kw_only,
+                    pos_only,
+                    aliases,
+                    named_as: None,
                     dot_dot: false,
                 }
             }
             None => Self {
+                ident: field.ident.ok_or_else(|| {
+                    syn::Error::new(field.ty.span(), "named field must have an identifier")
+                })?,
                 vis: field.vis,
                 attrs: field.attrs,
-                ident: field.ident.expect("named field must have an identifier"),
                 is_tuple: false,
                 ty: field.ty,
                 default_value,
+                kw_only,
+                pos_only,
+                aliases,
+                named_as: None,
                 dot_dot: false,
             },
         };
@@ -330,6 +472,11 @@ impl StructField {
         Ok(res)
     }
 
+    /// Macro metavariable that holds the value of this field.
+    fn metavar(&self) -> syn::Ident {
+        syn::Ident::new(&format!("{}_val", self.ident.unraw()), self.ident.span())
+    }
+
     /// Constructs a `StructField` that represents `..`.
     /// All other fields are irrelevant.
     pub fn dot_dot() -> Self {
@@ -343,6 +490,10 @@ impl StructField {
             is_tuple: false,
             ty: syn::parse_quote! {u8},
             default_value: ParamAttr::None,
+            kw_only: false,
+            pos_only: false,
+            aliases: vec![],
+            named_as: None,
             dot_dot: true,
         }
     }
@@ -392,7 +543,7 @@ mod tests {
 
         assert!(inner.len() == 3);
         assert!(matches!(inner[0].default_value, ParamAttr::None));
-        assert!(matches!(inner[1].default_value, ParamAttr::Default));
+        assert!(matches!(inner[1].default_value, ParamAttr::Default(_)));
         assert!(matches!(inner[2].default_value, ParamAttr::Value(_)));
     }
 
@@ -417,7 +568,7 @@ mod tests {
 
         assert!(inner.len() == 3);
         assert!(matches!(inner[0].default_value, ParamAttr::None));
-        assert!(matches!(inner[1].default_value, ParamAttr::Default));
+        assert!(matches!(inner[1].default_value, ParamAttr::Default(_)));
         assert!(matches!(inner[2].default_value, ParamAttr::Value(_)));
     }
 
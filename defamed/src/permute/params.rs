@@ -3,28 +3,42 @@
 use std::fmt::Debug;
 
 use quote::{quote, ToTokens};
-use syn::spanned::Spanned;
+use syn::{ext::IdentExt, spanned::Spanned};
 
-use crate::traits::{ToDocInfo, ToMacroPattern};
+use crate::traits::{ToDefaultCheck, ToDocInfo, ToMacroPattern, ToMacroSlot};
 
-use super::{ParamAttr, PermutedItem};
+use super::{Alias, ParamAttr, PermutedItem};
 
 /// Parsed function parameters
 #[derive(Clone)]
 pub struct FunctionParams {
     receiver: FnReceiver,
     pub params: Vec<FunctionParam>,
+    /// Trailing parameter collecting all remaining positional arguments
+    pub rest: Option<FunctionParam>,
 }
 
 /// Default function parameter
 #[derive(Clone)]
 pub struct FunctionParam {
-    /// Param name
+    /// Param pattern
     pat: syn::Pat,
+    /// Name of the parameter when passed by name, without the `r#` prefix of raw identifiers
+    name: syn::Ident,
+    /// Identifier the value is bound to, as written, such as `r#type`
+    binding: syn::Ident,
     ty: syn::Type,
     attrs: Vec<syn::Attribute>,
     /// A const that can be used as a default value
     pub default_value: ParamAttr,
+    /// Parameter can only be passed by name
+    pub kw_only: bool,
+    /// Parameter can only be passed by position
+    pub pos_only: bool,
+    /// Alternative names of the parameter
+    pub aliases: Vec<Alias>,
+    /// Alias used when passed by name, instead of the parameter name
+    named_as: Option<Alias>,
 }
 
 /// Function parameter receiver
@@ -33,7 +47,7 @@ pub enum FnReceiver {
     None,
     /// Self
     Slf {
-        ty: syn::Type,
+        ty: Box<syn::Type>,
         token: syn::Token![self],
         mutable: bool,
         reference: bool,
@@ -46,8 +60,12 @@ impl Debug for FunctionParam {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("FunctionParam")
             .field("pat", &self.pat.to_token_stream().to_string())
+            .field("name", &self.name.to_string())
             .field("ty", &self.ty.to_token_stream().to_string())
             .field("default_value", &self.default_value)
+            .field("kw_only", &self.kw_only)
+            .field("pos_only", &self.pos_only)
+            .field("aliases", &self.aliases)
             .finish()
     }
 }
@@ -56,8 +74,9 @@ impl Debug for ParamAttr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::None => write!(f, "None"),
-            Self::Default => write!(f, "Default"),
+            Self::Default(_) => write!(f, "Default"),
             Self::Value(arg0) => write!(f, "Value({})", arg0.to_token_stream()),
+            Self::Optional => write!(f, "Optional"),
         }
     }
 }
@@ -65,7 +84,7 @@ impl Debug for ParamAttr {
 // simple string matching
 impl PartialEq for FunctionParam {
     fn eq(&self, other: &Self) -> bool {
-        self.pat.to_token_stream().to_string() == other.pat.to_token_stream().to_string()
+        self.name == other.name
         // && self.ty == other.ty && self.default_value == other.default_value
     }
 }
@@ -73,13 +92,17 @@ impl PartialEq for FunctionParam {
 impl ToMacroPattern for PermutedItem<FunctionParam> {
     fn to_macro_pattern(&self) -> Option<proc_macro2::TokenStream> {
         match self {
-            PermutedItem::Positional(FunctionParam { pat, .. }) => {
-                let val = syn::Ident::new(&format!("{}_val", pat.to_token_stream()), pat.span());
+            PermutedItem::Positional(param) => {
+                let val = param.metavar();
                 Some(quote! {$#val: expr})
             }
-            PermutedItem::Named(FunctionParam { pat, .. }) => {
-                let val = syn::Ident::new(&format!("{}_val", pat.to_token_stream()), pat.span());
-                Some(quote! {#pat = $#val: expr})
+            PermutedItem::Named(param) => {
+                let name = match &param.named_as {
+                    Some(alias) => &alias.ident,
+                    None => &param.name,
+                };
+                let val = param.metavar();
+                Some(quote! {#name = $#val: expr})
             }
             PermutedItem::Default(_) => None,
         }
@@ -87,36 +110,95 @@ impl ToMacroPattern for PermutedItem<FunctionParam> {
 
     fn to_func_call_pattern(&self) -> proc_macro2::TokenStream {
         match self {
-            PermutedItem::Positional(FunctionParam { pat, .. })
-            | PermutedItem::Named(FunctionParam { pat, .. }) => {
-                let val = syn::Ident::new(&format!("{}_val", pat.to_token_stream()), pat.span());
-                quote! {$#val}
-            }
-            // PermutedItem::Named(FunctionParam { pat, .. }) =>{
-
-            // },
-            PermutedItem::Default(FunctionParam { default_value, .. }) => {
-                //
-                match default_value {
-                    ParamAttr::None => unimplemented!("default value must be present"),
-                    ParamAttr::Default => quote! {core::default::Default::default()},
-                    ParamAttr::Value(v) => quote! {#v},
-                }
+            PermutedItem::Positional(param) | PermutedItem::Named(param) => {
+                let val = param.metavar();
+                param.default_value.wrap(quote! {$#val})
             }
+            PermutedItem::Default(param) => param
+                .default_value
+                .default_expr()
+                .unwrap_or_else(|| super::missing_default(param.inner_span())),
         }
     }
 }
 
+impl ToMacroSlot for FunctionParam {
+    fn slot_metavar(&self) -> syn::Ident {
+        self.metavar()
+    }
+
+    fn slot_named(&self) -> bool {
+        !self.pos_only
+    }
+
+    fn slot_positional(&self) -> bool {
+        !self.kw_only
+    }
+
+    fn slot_aliases(&self) -> Vec<Self> {
+        self.aliases
+            .iter()
+            .map(|alias| Self {
+                named_as: Some(alias.clone()),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    fn slot_warning(&self) -> Option<proc_macro2::TokenStream> {
+        self.named_as
+            .as_ref()
+            .and_then(|alias| alias.warning(&self.name.to_string()))
+    }
+
+    fn slot_default(&self) -> Option<proc_macro2::TokenStream> {
+        self.default_value.default_expr()
+    }
+
+    fn slot_call(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        value
+    }
+
+    fn slot_wrap(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        self.default_value.wrap(value)
+    }
+
+    fn slot_binding(&self) -> syn::Ident {
+        match &self.pat {
+            syn::Pat::Ident(pat) => pat.ident.clone(),
+            _ => self.binding.clone(),
+        }
+    }
+}
+
+impl ToDefaultCheck for FunctionParam {
+    // bindings of destructuring patterns are not available where the macro is invoked
+    fn check_param(&self) -> proc_macro2::TokenStream {
+        let binding = self.slot_binding();
+        let ty = &self.ty;
+
+        quote! {#binding: #ty}
+    }
+
+    fn check_name(&self) -> Option<String> {
+        Some(self.name.to_string())
+    }
+
+    fn check_type(&self) -> &syn::Type {
+        &self.ty
+    }
+
+    fn check_default(&self) -> &ParamAttr {
+        &self.default_value
+    }
+}
+
 impl ToDocInfo for FunctionParam {
     fn to_doc_info(&self) -> crate::traits::DocInfo {
         crate::traits::DocInfo {
-            ident: self.pat.to_token_stream().to_string(),
-            ty: self.ty.to_token_stream().to_string(),
-            default_value: match &self.default_value {
-                ParamAttr::None => None,
-                ParamAttr::Default => Some("Default::default()".to_string()),
-                ParamAttr::Value(expr) => Some(expr.to_token_stream().to_string()),
-            },
+            ident: self.name.to_string(),
+            ty: super::type_string(&self.ty),
+            default_value: self.default_value.doc_value(),
         }
     }
 }
@@ -128,21 +210,26 @@ impl FunctionParams {
         let mut s = Self {
             receiver: FnReceiver::None,
             params: Vec::new(),
+            rest: None,
         };
         let mut has_receiver = false;
+        let mut errors = vec![];
 
         for punct in punctuated {
             match punct {
                 syn::FnArg::Receiver(recv) => {
-                    if !has_receiver {
-                        has_receiver = true;
-                    } else {
-                        panic!("Function cannot accept multiple receivers");
+                    if has_receiver {
+                        errors.push(syn::Error::new(
+                            recv.span(),
+                            "Function cannot accept multiple receivers",
+                        ));
+                        continue;
                     }
+                    has_receiver = true;
 
                     let receiver = match (&recv.reference, &recv.mutability) {
                         (None, None) => FnReceiver::Slf {
-                            ty: *recv.ty.clone(),
+                            ty: recv.ty.clone(),
                             token: recv.self_token,
                             mutable: false,
                             reference: false,
@@ -150,7 +237,7 @@ impl FunctionParams {
                             colon_token: recv.colon_token,
                         },
                         (None, Some(_)) => FnReceiver::Slf {
-                            ty: *recv.ty.clone(),
+                            ty: recv.ty.clone(),
                             token: recv.self_token,
                             mutable: true,
                             reference: false,
@@ -158,7 +245,7 @@ impl FunctionParams {
                             colon_token: recv.colon_token,
                         },
                         (Some(_), None) => FnReceiver::Slf {
-                            ty: *recv.ty.clone(),
+                            ty: recv.ty.clone(),
                             token: recv.self_token,
                             mutable: false,
                             reference: true,
@@ -166,7 +253,7 @@ impl FunctionParams {
                             colon_token: recv.colon_token,
                         },
                         (Some(_), Some(_)) => FnReceiver::Slf {
-                            ty: *recv.ty.clone(),
+                            ty: recv.ty.clone(),
                             token: recv.self_token,
                             mutable: true,
                             reference: true,
@@ -178,12 +265,66 @@ impl FunctionParams {
                     s.receiver = receiver;
                 }
                 syn::FnArg::Typed(t) => {
-                    let param = FunctionParam::from_pat_type(t)?;
-                    s.params.push(param);
+                    if let Some(rest) = &s.rest {
+                        errors.push(syn::Error::new(
+                            rest.inner_span(),
+                            format!("#[{}] must be the last parameter", crate::REST_HELPER_ATTR),
+                        ));
+                        break;
+                    }
+
+                    let is_rest = t
+                        .attrs
+                        .iter()
+                        .any(|a| a.path().is_ident(crate::REST_HELPER_ATTR));
+                    let param = match FunctionParam::from_pat_type(t) {
+                        Ok(param) => param,
+                        Err(e) => {
+                            errors.push(e);
+                            continue;
+                        }
+                    };
+
+                    match is_rest {
+                        true => match param.into_rest() {
+                            Ok(rest) => s.rest = Some(rest),
+                            Err(e) => errors.push(e),
+                        },
+                        false => s.params.push(param),
+                    }
                 }
             }
         }
 
+        super::combine_errors(errors)?;
+
+        super::check_aliases(
+            s.params
+                .iter()
+                .map(|p| (p.name.to_string(), p.aliases.as_slice())),
+        )?;
+
+        // names given with #[name] can clash with the names of other parameters
+        let mut names = std::collections::HashSet::new();
+        if let Some(p) = s.params.iter().find(|p| !names.insert(p.name.to_string())) {
+            return Err(syn::Error::new(
+                p.name.span(),
+                format!("`{}` is already used as a name", p.name),
+            ));
+        }
+
+        // rest arguments are only collected after all parameters are passed by position
+        if let (Some(rest), true) = (&s.rest, s.params.iter().any(|p| p.kw_only)) {
+            return Err(syn::Error::new(
+                rest.inner_span(),
+                format!(
+                    "#[{}] cannot be combined with #[{}] parameters",
+                    crate::REST_HELPER_ATTR,
+                    crate::KW_HELPER_ATTR
+                ),
+            ));
+        }
+
         Ok(s)
     }
 
@@ -215,18 +356,18 @@ impl FunctionParams {
                     },
                     self_token: *token,
                     colon_token: *colon_token,
-                    ty: Box::new(ty.clone()),
+                    ty: ty.clone(),
                 }));
             }
         }
 
-        for param in &self.params {
+        for param in self.params.iter().chain(&self.rest) {
             let pat = param.pat.to_owned();
             let ty = param.ty.to_owned();
             let s_attrs = param
                 .attrs
                 .iter()
-                .filter(|a| !a.path().is_ident(crate::DEFAULT_HELPER_ATTR))
+                .filter(|a| !super::is_helper_attr(a))
                 .cloned()
                 .collect::<Vec<_>>();
 
@@ -243,6 +384,27 @@ impl FunctionParams {
         res.into_iter().collect()
     }
 
+    /// Documentation info for the receiver, if any.
+    pub fn receiver_doc_info(&self) -> Option<crate::traits::DocInfo> {
+        match &self.receiver {
+            FnReceiver::None => None,
+            FnReceiver::Slf { ty, token, .. } => Some(crate::traits::DocInfo {
+                ident: token.to_token_stream().to_string(),
+                ty: super::type_string(ty),
+                default_value: None,
+            }),
+        }
+    }
+
+    /// Mark trailing `Option<T>` parameters as optional.
+    pub fn infer_optional(&mut self) {
+        super::infer_optional(
+            self.params
+                .iter_mut()
+                .map(|p| (&p.ty, &mut p.default_value)),
+        )
+    }
+
     /// Returns the first non-default item after the first default item, if any.
     pub fn first_invalid_param(&self) -> Option<&FunctionParam> {
         let mut iter = self
@@ -252,6 +414,40 @@ impl FunctionParams {
 
         iter.find(|f| matches!(f.default_value, ParamAttr::None))
     }
+
+    /// Returns the first positional parameter after the first keyword-only parameter, if any.
+    pub fn first_positional_after_kw(&self) -> Option<&FunctionParam> {
+        self.params
+            .iter()
+            .skip_while(|p| !p.kw_only)
+            .find(|p| !p.kw_only)
+    }
+
+    /// Number of trailing keyword-only parameters.
+    pub fn kw_only_count(&self) -> usize {
+        self.params.iter().filter(|p| p.kw_only).count()
+    }
+
+    /// Returns the first positional-only parameter after the first parameter that can be named, if any.
+    pub fn first_pos_only_after_named(&self) -> Option<&FunctionParam> {
+        self.params
+            .iter()
+            .skip_while(|p| p.pos_only)
+            .find(|p| p.pos_only)
+    }
+
+    /// Number of leading positional-only parameters.
+    pub fn pos_only_count(&self) -> usize {
+        self.params.iter().filter(|p| p.pos_only).count()
+    }
+
+    /// Upper bound on the number of ways the parameters of a single permutation can be named.
+    pub fn alias_variants(&self) -> usize {
+        self.params
+            .iter()
+            .map(|p| p.aliases.len() + 1)
+            .fold(1, usize::saturating_mul)
+    }
 }
 
 impl FunctionParam {
@@ -259,55 +455,146 @@ impl FunctionParam {
     pub fn from_pat_type(punct: syn::PatType) -> Result<Self, syn::Error> {
         let pat = &punct.pat;
         let ty = &punct.ty;
-        let mut default_value = ParamAttr::None;
-
-        // look for default attr
-        if !punct.attrs.is_empty() {
-            for attr in &punct.attrs {
-                if attr.path().is_ident(crate::DEFAULT_HELPER_ATTR) {
-                    let meta = attr.meta.clone();
-
-                    match meta {
-                        syn::Meta::Path(_) => default_value = ParamAttr::Default,
-                        syn::Meta::List(l) => {
-                            let l_span = l.span();
-
-                            let first_item = l.tokens.into_iter().next().ok_or(syn::Error::new(
-                                l_span,
-                                "expected at least 1 item in metalist",
-                            ))?;
-
-                            let e: syn::Expr = syn::parse2(first_item.to_token_stream())?;
-                            default_value = ParamAttr::Value(e);
-                        }
-                        syn::Meta::NameValue(nv) => {
-                            let e = syn::Error::new(
-                                    nv.span(),
-                                    format!("name-values are not supported. Use #[{}] or #[{}(CONST_EXPRESSION)] instead.",
-                                        crate::DEFAULT_HELPER_ATTR,
-                                        crate::DEFAULT_HELPER_ATTR
-                                    ),
-                                );
-                            return Err(e);
-                        }
-                    }
+        let default_value = ParamAttr::from_attrs(&punct.attrs, ty)?;
+        let kw_only = super::has_marker_attr(&punct.attrs, crate::KW_HELPER_ATTR)?;
+        let pos_only = super::has_marker_attr(&punct.attrs, crate::POS_HELPER_ATTR)?;
+        let aliases = Alias::from_attrs(&punct.attrs)?;
+        let binding = Self::param_binding(pat, &punct.attrs)?;
+        let name = binding.unraw();
+
+        if kw_only && pos_only {
+            return Err(syn::Error::new(
+                pat.span(),
+                format!(
+                    "only one of #[{}] or #[{}] can be used",
+                    crate::KW_HELPER_ATTR,
+                    crate::POS_HELPER_ATTR
+                ),
+            ));
+        }
 
-                    break;
-                }
-            }
+        if let (Some(alias), true) = (aliases.first(), pos_only) {
+            return Err(syn::Error::new(
+                alias.ident.span(),
+                format!(
+                    "#[{}] parameters cannot be passed by name",
+                    crate::POS_HELPER_ATTR
+                ),
+            ));
         }
 
         Ok(Self {
             pat: *pat.clone(),
+            name,
+            binding,
             ty: *ty.clone(),
             attrs: punct.attrs,
             default_value,
+            kw_only,
+            pos_only,
+            aliases,
+            named_as: None,
         })
     }
 
+    /// Identifier of a parameter, given by `#[name(x)]` or the identifier it is bound to.
+    ///
+    /// Destructuring patterns do not bind a single identifier and must be named with `#[name(x)]`,
+    /// which is bound to the whole value where the macro is invoked.
+    fn param_binding(pat: &syn::Pat, attrs: &[syn::Attribute]) -> Result<syn::Ident, syn::Error> {
+        if let Some(attr) = attrs
+            .iter()
+            .find(|a| a.path().is_ident(crate::NAME_HELPER_ATTR))
+        {
+            return match &attr.meta {
+                syn::Meta::List(l) => l.parse_args::<syn::Ident>(),
+                _ => Err(syn::Error::new(
+                    attr.span(),
+                    format!("expected a name, such as #[{}(x)]", crate::NAME_HELPER_ATTR),
+                )),
+            };
+        }
+
+        match pat {
+            syn::Pat::Ident(pat) => Ok(pat.ident.clone()),
+            _ => Err(syn::Error::new(
+                pat.span(),
+                format!(
+                    "parameters with destructuring or wildcard patterns must be named with #[{}(x)]",
+                    crate::NAME_HELPER_ATTR
+                ),
+            )),
+        }
+    }
+
     pub fn inner_span(&self) -> proc_macro2::Span {
         self.pat.span()
     }
+
+    /// Validate `Self` as a rest parameter.
+    fn into_rest(self) -> Result<Self, syn::Error> {
+        if let Some(alias) = self.aliases.first() {
+            return Err(syn::Error::new(
+                alias.ident.span(),
+                format!(
+                    "#[{}] parameters cannot be passed by name",
+                    crate::REST_HELPER_ATTR
+                ),
+            ));
+        }
+
+        if self.kw_only {
+            return Err(syn::Error::new(
+                self.inner_span(),
+                format!(
+                    "#[{}] parameters cannot be keyword-only",
+                    crate::REST_HELPER_ATTR
+                ),
+            ));
+        }
+
+        if !matches!(self.default_value, ParamAttr::None) {
+            return Err(syn::Error::new(
+                self.inner_span(),
+                format!(
+                    "#[{}] parameters cannot have a default value",
+                    crate::REST_HELPER_ATTR
+                ),
+            ));
+        }
+
+        match &self.ty {
+            syn::Type::Reference(r) if !matches!(*r.elem, syn::Type::Slice(_)) => {
+                Err(syn::Error::new(
+                    self.ty.span(),
+                    format!(
+                        "#[{}] references must be slices, such as `&[T]`",
+                        crate::REST_HELPER_ATTR
+                    ),
+                ))
+            }
+            _ => Ok(self),
+        }
+    }
+
+    /// Convert collected arguments into the value of this rest parameter.
+    ///
+    /// Slice references borrow an array, all other types are converted from an array with [From].
+    pub fn rest_collect(&self, args: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match &self.ty {
+            syn::Type::Reference(syn::TypeReference {
+                mutability: Some(_),
+                ..
+            }) => quote! {&mut [#args]},
+            syn::Type::Reference(_) => quote! {&[#args]},
+            _ => quote! {core::convert::From::from([#args])},
+        }
+    }
+
+    /// Macro metavariable that holds the value of this parameter.
+    fn metavar(&self) -> syn::Ident {
+        syn::Ident::new(&format!("{}_val", self.name), self.pat.span())
+    }
 }
 
 #[cfg(test)]
@@ -318,6 +605,12 @@ mod tests {
     use quote::quote;
     use syn::{punctuated::Punctuated, token::Comma, FnArg};
 
+    /// Parse the parameters of a function item.
+    fn parse(item_fn: proc_macro2::TokenStream) -> Result<FunctionParams, syn::Error> {
+        let item_fn: syn::ItemFn = syn::parse2(item_fn).unwrap();
+        FunctionParams::from_punctuated(item_fn.sig.inputs)
+    }
+
     #[test]
     fn test_init_params() {
         let default_ident =
@@ -340,6 +633,111 @@ mod tests {
         assert_eq!(params.params.len(), 4);
     }
 
+    #[test]
+    fn test_rest_param() {
+        let params = parse(quote! {fn f(a: u8, #[def] b: u8, #[rest] c: Vec<u8>) {}}).unwrap();
+        assert_eq!(params.params.len(), 2);
+        assert!(params.rest.is_some());
+        assert_eq!(params.to_punctuated().len(), 3);
+
+        assert!(parse(quote! {fn f(#[rest] c: &[u8]) {}}).is_ok());
+        assert!(parse(quote! {fn f(#[rest] c: &mut [u8]) {}}).is_ok());
+        assert!(parse(quote! {fn f(#[rest] c: [u8; 2]) {}}).is_ok());
+
+        assert!(parse(quote! {fn f(#[rest] c: Vec<u8>, d: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(#[rest] #[def] c: Vec<u8>) {}}).is_err());
+        assert!(parse(quote! {fn f(#[rest] c: &Vec<u8>) {}}).is_err());
+    }
+
+    #[test]
+    fn test_kw_only_param() {
+        let params = parse(quote! {fn f(a: u8, #[kw] b: bool, #[kw] #[def] c: bool) {}}).unwrap();
+        assert!(!params.params[0].kw_only);
+        assert!(params.params[1].kw_only);
+        assert_eq!(params.kw_only_count(), 2);
+        assert!(params.first_positional_after_kw().is_none());
+        assert!(params.to_punctuated().iter().all(|arg| match arg {
+            FnArg::Typed(t) => t.attrs.is_empty(),
+            FnArg::Receiver(_) => true,
+        }));
+
+        let params = parse(quote! {fn f(#[kw] a: u8, b: u8) {}}).unwrap();
+        assert_eq!(params.first_positional_after_kw(), Some(&params.params[1]));
+
+        assert!(parse(quote! {fn f(#[kw(1)] a: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(#[kw] #[rest] a: Vec<u8>) {}}).is_err());
+        assert!(parse(quote! {fn f(#[kw] a: u8, #[rest] b: Vec<u8>) {}}).is_err());
+    }
+
+    #[test]
+    fn test_pos_only_param() {
+        let params = parse(quote! {fn f(#[pos] a: u8, #[pos] #[def] b: u8, c: u8) {}}).unwrap();
+        assert!(params.params[0].pos_only);
+        assert!(!params.params[2].pos_only);
+        assert!(!params.params[0].slot_named());
+        assert_eq!(params.pos_only_count(), 2);
+        assert!(params.first_pos_only_after_named().is_none());
+
+        let params = parse(quote! {fn f(a: u8, #[pos] b: u8) {}}).unwrap();
+        assert_eq!(params.first_pos_only_after_named(), Some(&params.params[1]));
+
+        assert!(parse(quote! {fn f(#[pos(a)] a: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(#[pos] #[kw] a: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(#[pos] a: u8, #[rest] b: Vec<u8>) {}}).is_ok());
+    }
+
+    #[test]
+    fn test_param_aliases() {
+        let params =
+            parse(quote! {fn f(#[alias(x)] a: u8, #[deprecated_name(y, z)] b: u8) {}}).unwrap();
+        assert_eq!(params.alias_variants(), 6);
+
+        let aliased = params.params[1].slot_aliases();
+        assert_eq!(aliased.len(), 2);
+        assert!(aliased.iter().all(|p| *p == params.params[1]));
+        assert!(aliased.iter().all(|p| p.slot_warning().is_some()));
+        assert!(params.params[1].slot_warning().is_none());
+        assert!(params.params[0].slot_aliases()[0].slot_warning().is_none());
+
+        let pattern = PermutedItem::Named(aliased[0].clone())
+            .to_macro_pattern()
+            .unwrap();
+        assert_eq!(pattern.to_string(), quote! {y = $b_val: expr}.to_string());
+
+        assert!(parse(quote! {fn f(#[alias(b)] a: u8, b: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(#[pos] #[alias(b)] a: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(#[rest] #[alias(b)] a: Vec<u8>) {}}).is_err());
+    }
+
+    #[test]
+    fn test_param_patterns() {
+        let params = parse(quote! {
+            fn f(mut a: u8, ref b: u8, r#type: u8, #[name(point)] (x, y): (u8, u8)) {}
+        })
+        .unwrap();
+        let names = params
+            .params
+            .iter()
+            .map(|p| p.to_doc_info().ident)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a", "b", "type", "point"]);
+
+        let pattern = PermutedItem::Named(params.params[2].clone())
+            .to_macro_pattern()
+            .unwrap();
+        assert_eq!(
+            pattern.to_string(),
+            quote! {type = $type_val: expr}.to_string()
+        );
+        assert_eq!(params.params[2].slot_binding().to_string(), "r#type");
+        assert_eq!(params.params[3].slot_binding().to_string(), "point");
+
+        assert!(parse(quote! {fn f((x, y): (u8, u8)) {}}).is_err());
+        assert!(parse(quote! {fn f(_: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(#[name] _: u8) {}}).is_err());
+        assert!(parse(quote! {fn f(a: u8, #[name(a)] _: u8) {}}).is_err());
+    }
+
     #[test]
     fn test_first_invalid_param() {
         let default_attr = syn::Ident::new(crate::DEFAULT_HELPER_ATTR, Span::call_site());
@@ -1,4 +1,5 @@
 use proc_macro2 as pm2;
+use quote::ToTokens;
 
 /// Turn `Self` into fragments of rust code.
 pub trait ToMacroPattern {
@@ -30,6 +31,68 @@ pub trait ToMacroPattern {
     fn to_func_call_pattern(&self) -> pm2::TokenStream;
 }
 
+/// Describe `Self` as a single argument slot of a linear (tt-muncher) macro.
+///
+/// ```ignore
+/// macro_rules! some_macro (
+///     (@__defamed named [$pos_a_val:tt] [$($pos_b_val:tt)?] ; ) => {
+///     //                 ^^^^^^^^^^^^^^ this is one slot
+///         function_call($pos_a_val, ...)
+///     //                ^^^^^^^^^^ value of the slot placed in the call
+///     }
+/// )
+/// ```
+pub trait ToMacroSlot {
+    /// Metavariable holding the value of the slot.
+    fn slot_metavar(&self) -> syn::Ident;
+
+    /// Returns `true` if the slot can be passed as a named argument.
+    fn slot_named(&self) -> bool;
+
+    /// Returns `true` if the slot can be passed as a positional argument.
+    fn slot_positional(&self) -> bool;
+
+    /// Copies of the slot that are passed by each of its aliases.
+    fn slot_aliases(&self) -> Vec<Self>
+    where
+        Self: Sized;
+
+    /// Deprecation warning emitted when the slot is passed by its current name, if any.
+    fn slot_warning(&self) -> Option<pm2::TokenStream>;
+
+    /// Expression substituted when the slot is not filled. `None` for required slots.
+    fn slot_default(&self) -> Option<pm2::TokenStream>;
+
+    /// Place a resolved value inside the function call or struct literal.
+    fn slot_call(&self, value: pm2::TokenStream) -> pm2::TokenStream;
+
+    /// Convert a supplied argument into the value passed to the item.
+    fn slot_wrap(&self, value: pm2::TokenStream) -> pm2::TokenStream;
+
+    /// Local variable the value is bound to, when default values refer to preceding slots.
+    fn slot_binding(&self) -> syn::Ident;
+
+    /// Place the bound local inside the function call or struct literal.
+    fn slot_bound_call(&self) -> pm2::TokenStream {
+        self.slot_call(self.slot_binding().into_token_stream())
+    }
+}
+
+/// Describe `Self` as a parameter of the hidden function that checks default values where the item is defined.
+pub trait ToDefaultCheck {
+    /// Parameter of the function, such as `a: u8`.
+    fn check_param(&self) -> pm2::TokenStream;
+
+    /// Name of the parameter or field, `None` for tuple fields.
+    fn check_name(&self) -> Option<String>;
+
+    /// Type of the parameter or field.
+    fn check_type(&self) -> &syn::Type;
+
+    /// Default value of the parameter or field.
+    fn check_default(&self) -> &crate::permute::ParamAttr;
+}
+
 /// Strip matching attributes from a type.
 /// For function parameters, this is the `#[def]` attribute.
 pub trait StripAttributes {
@@ -16,4 +16,17 @@ fn main() {
     let r_1 = defamed_test_lib::some_root_function("base", None);
     let r_2 = defamed_test_lib::some_root_function!("base");
     assert_eq!(r_1, r_2);
+
+    // macros generated with the linear strategy are used the same way
+    let many = defamed_test_lib::many_args!(1, 2, 3, 4, 5, 6, o = 0);
+    println!("many args: {}", many);
+
+    // methods are wrapped by macros named after the type and method
+    let mut counter = defamed_test_lib::Counter_new!(step = 2);
+    defamed_test_lib::Counter_increment!(&mut counter, times = 3);
+    println!("counter: {}", counter.count);
+
+    // nested modules work at any depth
+    let request = defamed_test_lib::net::http::client::request!("/index.html");
+    println!("request: {}", request);
 }